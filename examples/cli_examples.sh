@@ -21,6 +21,81 @@ rasterfakers -o noise.tiff \
     -t f32 \
     -r "0.01,0.01"
 
+# Seeded random patterns with parameters
+rasterfakers -o uniform.tiff -t u8 -n "uniform:min=0:max=255" --seed 1
+rasterfakers -o poisson.tiff -t u16 -n "poisson:lambda=20" --seed 2
+
+# Coherent noise
+rasterfakers -o perlin.tiff -t f32 -n "perlin:frequency=0.02" --seed 3
+rasterfakers -o fbm.tiff -t f32 -w 1024 -e 1024 --threads 0 \
+    -n "fbm:basis=simplex:frequency=0.004:octaves=6:lacunarity=2:persistence=0.5"
+
+# Synthetic DEMs
+rasterfakers -o dem.tiff -w 1024 -e 1024 --preset dem --seed 11
+rasterfakers -o dem_i16.tiff --preset dem -t i16 \
+    -n "terrain:min=0:max=3500:roughness=0.8:size=128"
+
+# Test cards for checking reprojection and rendering
+rasterfakers -o checkerboard.tiff -t u8 -w 512 -e 512 -n checkerboard:size=32
+rasterfakers -o stripes.tiff -t u8 -n stripes:width=4:direction=vertical
+rasterfakers -o rings.tiff -t u8 -w 512 -e 512 -n rings:width=16
+rasterfakers -o radial.tiff -t u8 -n radial
+rasterfakers -o grid.tiff -t u8 -w 720 -e 360 -r 0.5,0.5 -c=-180,90 -n grid:spacing=10:line_width=3
+rasterfakers -o siemens.tiff -t u8 -w 1024 -e 1024 -n siemens:spokes=36
+
+# Land cover classes, with a colour table and category names
+rasterfakers -o land_cover.tiff -t u8 -w 1024 -e 1024 --seed 5 \
+    -n "categorical:classes=10/20/30/40:proportions=4/3/2/1:size=48" \
+    --color-table "10=#1a9850,20=#d9ef8b,30=#4575b4,40=#d73027" \
+    --category-names "10=Forest,20=Grassland,30=Water,40=Urban"
+
+# The same classes with a raster attribute table holding their pixel counts
+rasterfakers -o land_cover_rat.tiff -t u8 -w 1024 -e 1024 --seed 5 \
+    -n "categorical:classes=10/20/30/40:proportions=4/3/2/1:size=48" \
+    --category-names "10=Forest,20=Grassland,30=Water,40=Urban" --attribute-table
+
+# Palette images: a ramp over all 256 values, or distinct random colours per class
+rasterfakers -o palette_viridis.tiff -t u8 -w 512 -e 512 -n terrain --min 0 --max 255 \
+    --color-table viridis
+rasterfakers -o palette_random.tiff -t u8 -w 512 -e 512 --seed 3 \
+    -n "categorical:classes=0/1/2/3/4/5/6/7:size=32" --color-table random:8
+
+# Expression patterns; map_x and map_y are map coordinates of pixel centres
+rasterfakers -o expr.tiff -b 3 -t f32 -n 'expr:sin(x/10)*cos(y/10)*100 + band'
+rasterfakers -o lat.tiff -t f32 -c=-180,90 -r 1,1 -w 360 -e 180 -n 'expr:30 - abs(map_y) / 2'
+
+# BigTIFF, generated block by block with bounded memory
+# (scale up to e.g. -w 100000 -e 100000 for a multi-gigabyte fixture)
+rasterfakers -o big.tiff -w 8192 -e 8192 -t u8 -n constant:value=1 --bigtiff yes
+
+# RGB + NIR fixture with a different pattern per band
+rasterfakers -o rgbn.tiff -b 4 -t u16 -n "gradient,noise,sine,perlin:frequency=0.01"
+
+# Fit Perlin noise into 0-100 percent
+rasterfakers -o percent.tiff -t u8 -n perlin --min 0 --max 100
+
+# Stretch the noise pattern's full range over u8 instead of saturating it
+rasterfakers -o rescaled.tiff -t u8 -n noise --conversion rescale
+
+# Complex SAR-like data, with independent Gaussian real and imaginary parts
+rasterfakers -o slc.tiff -t cf32 -n "gaussian:mean=0:std_dev=1" \
+    --imaginary-pattern "gaussian:mean=0:std_dev=1" --seed 3
+
+# Compression codecs, levels and predictors
+rasterfakers -o deflate.tiff -t u16 --compress deflate --level 9 --predictor horizontal
+rasterfakers -o jpeg.tiff -t u8 -b 3 -n noise --compress jpeg --level 85
+
+# Explicit layouts: 512x512 tiles, or odd-sized strips
+rasterfakers -o tiled.tiff -w 2048 -e 2048 -t u8 --tile-size 512
+rasterfakers -o strips.tiff -w 1000 -e 1000 -t u16 --rows-per-strip 7
+
+# Band interleaved multiband GeoTIFF
+rasterfakers -o band_interleaved.tiff -b 4 -t u16 -n noise --interleave band
+
+# Overviews with average resampling, inside the file or in an external .ovr
+rasterfakers -o overviews.tiff -w 4096 -e 4096 -t u8 --overviews 2,4,8 --resampling average
+rasterfakers -o external.tiff -w 4096 -e 4096 -t u8 --overviews auto:128 --external-overviews
+
 # Cloud Optimized GeoTIFF
 rasterfakers -o cog_basic.tiff --cloud-optimized
 
@@ -35,4 +110,11 @@ rasterfakers -o cog_custom.tiff \
     -r "0.01,0.01" \
     --cloud-optimized
 
+# NoData value with reproducible blob-shaped holes
+rasterfakers -o holes.tiff \
+    -t i16 \
+    --nodata -9999 \
+    --nodata-holes "blobs:0.2:32" \
+    --seed 42
+
 echo "All example GeoTIFFs generated successfully!"
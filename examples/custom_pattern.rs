@@ -1,5 +1,6 @@
 use rasterfakers::{DataGenerator, FakeGeoTiffBuilder};
 
+// A hand-rolled version of the built-in `CheckerboardPattern::new(1)`
 struct ChessboardPattern;
 
 impl DataGenerator for ChessboardPattern {
@@ -22,5 +23,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     geotiff.write()?;
     println!("Chessboard pattern GeoTIFF generated!");
+
+    // One-off patterns can be closures instead of named structs
+    let geotiff = FakeGeoTiffBuilder::new()
+        .dimensions(512, 512)?
+        .output_path("xor.tiff")
+        .data_fn(|x, y, _band| ((x ^ y) % 256) as f64)
+        .build::<u8>()?;
+
+    geotiff.write()?;
+    println!("XOR pattern GeoTIFF generated!");
     Ok(())
 }
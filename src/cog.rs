@@ -0,0 +1,182 @@
+use crate::error::{GeoTiffError, Result};
+use gdal::raster::RasterBand;
+use gdal::{Dataset, Metadata};
+use std::io::Read;
+use std::path::Path;
+
+/// The prefix of the structural metadata GDAL writes right after the TIFF
+/// header of a COG, followed by its size as six digits and " bytes\n".
+const GHOST_AREA_PREFIX: &[u8] = b"GDAL_STRUCTURAL_METADATA_SIZE=";
+
+/// Checks that the file at `path` is a valid cloud optimized GeoTIFF.
+///
+/// This follows GDAL's `validate_cloud_optimized_geotiff.py`: the file must
+/// be a GeoTIFF, tiled if it's larger than 512x512, with internal overviews,
+/// the first IFD right after the header, all IFDs (masks included) before
+/// the image data, and the image data ordered from the smallest overview to
+/// the full resolution image.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rasterfakers::{validate_cog, FakeGeoTiffBuilder};
+///
+/// FakeGeoTiffBuilder::new()
+///     .cloud_optimized(true)
+///     .output_path("cog.tiff")
+///     .build::<u8>().unwrap()
+///     .write().unwrap();
+///
+/// validate_cog("cog.tiff").unwrap();
+/// ```
+pub fn validate_cog<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    let dataset = Dataset::open(path)?;
+
+    if dataset.driver().short_name() != "GTiff" {
+        return Err(invalid("The file is not a GeoTIFF"));
+    }
+
+    let mut external_overviews = path.as_os_str().to_owned();
+    external_overviews.push(".ovr");
+    if Path::new(&external_overviews).exists() {
+        return Err(invalid(
+            "Overviews found in an external .ovr file, they should be internal",
+        ));
+    }
+
+    let main_band = dataset.rasterband(1)?;
+    let (width, height) = main_band.size();
+    let needs_tiling = width > 512 || height > 512;
+    if needs_tiling && !is_tiled(&main_band) {
+        return Err(invalid("The file is larger than 512x512 but is not tiled"));
+    }
+
+    let mut images = vec![main_band];
+    for index in 0..images[0].overview_count()? as usize {
+        let overview = images[0].overview(index)?;
+        if needs_tiling && !is_tiled(&overview) {
+            return Err(invalid(format!("Overview {} is not tiled", index)));
+        }
+        images.push(overview);
+    }
+
+    // Image 0 is the full resolution image, image i + 1 overview i
+    let name = |image: usize| match image {
+        0 => "the main image".to_string(),
+        _ => format!("overview {}", image - 1),
+    };
+
+    // Each image's IFD is followed by the IFD of its mask, if it has one
+    let mut ifds = Vec::new();
+    let mut masks = Vec::new();
+    for (image, band) in images.iter().enumerate() {
+        ifds.push((name(image), tiff_offset(band, "IFD_OFFSET")?));
+        if band.mask_flags()?.is_per_dataset() {
+            let mask = band.open_mask_band()?;
+            let mask_name = format!("the mask of {}", name(image));
+            ifds.push((mask_name.clone(), tiff_offset(&mask, "IFD_OFFSET")?));
+            masks.push((mask_name, tiff_offset(&mask, "BLOCK_OFFSET_0_0")?));
+        }
+    }
+
+    let expected_first_ifd = first_ifd_offset(path)?;
+    if ifds[0].1 != expected_first_ifd {
+        return Err(invalid(format!(
+            "The IFD of the main image is at byte {}, not right after the header at byte {}",
+            ifds[0].1, expected_first_ifd
+        )));
+    }
+    for pair in ifds.windows(2) {
+        let ((previous, previous_offset), (current, offset)) = (&pair[0], &pair[1]);
+        if offset < previous_offset {
+            return Err(invalid(format!(
+                "The IFD of {} is at byte {}, before the IFD of {} at byte {}",
+                current, offset, previous, previous_offset
+            )));
+        }
+    }
+
+    // Empty blocks have no data, and an offset of 0
+    let data_offsets = images
+        .iter()
+        .map(|band| tiff_offset(band, "BLOCK_OFFSET_0_0"))
+        .collect::<Result<Vec<_>>>()?;
+    let last_ifd = ifds[ifds.len() - 1].1;
+    let first_blocks = data_offsets
+        .iter()
+        .enumerate()
+        .map(|(image, &offset)| (name(image), offset))
+        .chain(masks);
+    for (image, offset) in first_blocks {
+        if offset != 0 && offset < last_ifd {
+            return Err(invalid(format!(
+                "The first block of {} is at byte {}, before the last IFD at byte {}",
+                image, offset, last_ifd
+            )));
+        }
+    }
+    for image in 0..data_offsets.len().saturating_sub(1) {
+        let (offset, next) = (data_offsets[image], data_offsets[image + 1]);
+        if offset != 0 && offset < next {
+            return Err(invalid(format!(
+                "The first block of {} is at byte {}, before the first block of {} at byte {}",
+                name(image),
+                offset,
+                name(image + 1),
+                next
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn invalid(message: impl Into<String>) -> GeoTiffError {
+    GeoTiffError::InvalidCog(message.into())
+}
+
+/// Where the first IFD of a COG belongs: right after the TIFF header, 8
+/// bytes for classic TIFF and 16 for BigTIFF, and the structural metadata
+/// GDAL may write there, padded to an even size.
+fn first_ifd_offset(path: &Path) -> Result<u64> {
+    let mut start = Vec::with_capacity(1024);
+    std::fs::File::open(path)
+        .and_then(|file| file.take(1024).read_to_end(&mut start))
+        .map_err(|e| invalid(format!("Cannot read the TIFF header: {}", e)))?;
+    if start.len() < 8 {
+        return Err(invalid("The file is too short for a TIFF header"));
+    }
+
+    let version = match &start[..2] {
+        b"II" => u16::from_le_bytes([start[2], start[3]]),
+        _ => u16::from_be_bytes([start[2], start[3]]),
+    };
+    let header_size = if version == 43 { 16 } else { 8 };
+
+    let ghost_area = &start[header_size.min(start.len())..];
+    let mut offset = header_size as u64;
+    if let Some(rest) = ghost_area.strip_prefix(GHOST_AREA_PREFIX) {
+        let size = std::str::from_utf8(&rest[..6.min(rest.len())])
+            .ok()
+            .and_then(|digits| digits.parse::<u64>().ok())
+            .ok_or_else(|| invalid("Invalid size of the GDAL structural metadata"))?;
+        // The prefix, six digits and " bytes\n", then the metadata itself
+        offset += (GHOST_AREA_PREFIX.len() + 6 + 7) as u64 + size;
+        offset += offset % 2;
+    }
+    Ok(offset)
+}
+
+fn is_tiled(band: &RasterBand) -> bool {
+    let (block_width, _) = band.block_size();
+    let (width, _) = band.size();
+    block_width != width || block_width <= 1024
+}
+
+fn tiff_offset(band: &RasterBand, key: &str) -> Result<u64> {
+    band.metadata_item(key, "TIFF")
+        .unwrap_or_default()
+        .parse()
+        .map_err(|_| invalid(format!("Missing or invalid {} in the TIFF metadata", key)))
+}
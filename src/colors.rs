@@ -0,0 +1,254 @@
+use crate::error::{GeoTiffError, Result};
+use crate::patterns::{mix64, unit_f64};
+use gdal::raster::ColorEntry;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// An RGBA colour with 8 bits per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    /// Creates an opaque colour.
+    pub fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    pub fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+impl FromStr for Color {
+    type Err = GeoTiffError;
+
+    /// Parses a hexadecimal colour, `#RRGGBB` or `#RRGGBBAA`, with an
+    /// optional leading `#`.
+    fn from_str(s: &str) -> Result<Self> {
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        let invalid =
+            || GeoTiffError::InvalidParameter(format!("Invalid color '{}', expected #RRGGBB", s));
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid());
+        let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+        Ok(Color::rgba(channel(0)?, channel(2)?, channel(4)?, alpha))
+    }
+}
+
+/// A colour table mapping pixel values to colours, written to the first
+/// band of a GeoTIFF with
+/// [`FakeGeoTiffBuilder::color_table`](crate::FakeGeoTiffBuilder::color_table),
+/// which makes it a palette (colour-mapped) image.
+///
+/// Values without a colour of their own are transparent black. Besides
+/// listing colours one by one, tables can be generated from a
+/// [`ColorRamp`] or as a set of [`distinct`](Self::distinct) colours.
+///
+/// # Examples
+///
+/// ```
+/// use rasterfakers::colors::{Color, ColorTable};
+///
+/// let land_cover = ColorTable::new()
+///     .color(1, Color::rgb(0x1a, 0x98, 0x50))
+///     .color(2, "#4575b4".parse().unwrap());
+///
+/// assert_eq!(land_cover.get(2), Some(Color::rgb(0x45, 0x75, 0xb4)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColorTable {
+    colors: BTreeMap<u16, Color>,
+}
+
+impl ColorTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spreads `ramp` over the values `0..count`, e.g. 256 for a `u8` band.
+    ///
+    /// `count` is capped at 65536, the number of `u16` values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::colors::{Color, ColorRamp, ColorTable};
+    ///
+    /// let viridis = ColorTable::ramp(ColorRamp::Viridis, 256);
+    /// assert_eq!(viridis.get(0), Some(Color::rgb(0x44, 0x01, 0x54)));
+    /// assert_eq!(viridis.get(255), Some(Color::rgb(0xfd, 0xe7, 0x25)));
+    /// ```
+    pub fn ramp(ramp: ColorRamp, count: usize) -> Self {
+        let count = count.min(1 << 16);
+        let last = count.saturating_sub(1).max(1) as f64;
+        (0..count)
+            .map(|value| (value as u16, ramp.color_at(value as f64 / last)))
+            .collect()
+    }
+
+    /// Gives the values `0..count` distinct, random colours, as for the
+    /// classes of a categorical raster.
+    ///
+    /// Hues are spread around the colour wheel by the golden angle, so
+    /// consecutive values get clearly different colours; saturation and
+    /// brightness vary too, to tell apart the hues that come close once
+    /// there are many colours. The same `seed` always gives the same colours.
+    /// `count` is capped at 65536, the number of `u16` values.
+    pub fn distinct(count: usize, seed: u64) -> Self {
+        const GOLDEN_ANGLE: f64 = 0.381_966_011_250_105;
+        let start = unit_f64(mix64(seed));
+        (0..count.min(1 << 16))
+            .map(|value| {
+                let hue = (start + value as f64 * GOLDEN_ANGLE).fract();
+                let saturation = [0.75, 0.55, 0.95][value % 3];
+                let brightness = [0.9, 0.7, 0.8, 0.6][value / 3 % 4];
+                (value as u16, hsv(hue, saturation, brightness))
+            })
+            .collect()
+    }
+
+    /// Sets the colour of pixels with the given value.
+    pub fn color(mut self, value: u16, color: Color) -> Self {
+        self.colors.insert(value, color);
+        self
+    }
+
+    pub fn get(&self, value: u16) -> Option<Color> {
+        self.colors.get(&value).copied()
+    }
+
+    /// Iterates over the values with a colour, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, Color)> + '_ {
+        self.colors.iter().map(|(&value, &color)| (value, color))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// The highest value with a colour.
+    pub(crate) fn max_value(&self) -> Option<u16> {
+        self.colors.keys().next_back().copied()
+    }
+
+    pub(crate) fn to_gdal(&self) -> gdal::raster::ColorTable<'static> {
+        let mut table = gdal::raster::ColorTable::default();
+        for (value, color) in self.iter() {
+            let entry = ColorEntry::rgba(
+                color.r.into(),
+                color.g.into(),
+                color.b.into(),
+                color.a.into(),
+            );
+            table.set_color_entry(value, &entry);
+        }
+        table
+    }
+}
+
+impl FromIterator<(u16, Color)> for ColorTable {
+    fn from_iter<I: IntoIterator<Item = (u16, Color)>>(iter: I) -> Self {
+        Self {
+            colors: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A named colour ramp, for continuous data shown as a palette image.
+///
+/// The perceptually uniform ramps are interpolated from nine samples of
+/// their matplotlib definitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorRamp {
+    Viridis,
+    Magma,
+    Inferno,
+    Plasma,
+    /// Black to white.
+    Grayscale,
+}
+
+impl ColorRamp {
+    fn stops(self) -> &'static [u32] {
+        match self {
+            ColorRamp::Viridis => &[
+                0x440154, 0x472c7a, 0x3b518b, 0x2c718e, 0x21908d, 0x27ad81, 0x5cc863, 0xaadc32,
+                0xfde725,
+            ],
+            ColorRamp::Magma => &[
+                0x000004, 0x1c1044, 0x4f127b, 0x812581, 0xb5367a, 0xe55964, 0xfb8761, 0xfec287,
+                0xfcfdbf,
+            ],
+            ColorRamp::Inferno => &[
+                0x000004, 0x1f0c48, 0x550f6d, 0x88226a, 0xba3655, 0xe35933, 0xf98e09, 0xf9cb35,
+                0xfcffa4,
+            ],
+            ColorRamp::Plasma => &[
+                0x0d0887, 0x4c02a1, 0x7e03a8, 0xa82296, 0xcb4679, 0xe56b5d, 0xf89441, 0xfdc328,
+                0xf0f921,
+            ],
+            ColorRamp::Grayscale => &[0x000000, 0xffffff],
+        }
+    }
+
+    /// The colour at `t`, from 0 at the start of the ramp to 1 at its end.
+    pub fn color_at(self, t: f64) -> Color {
+        let stops = self.stops();
+        let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let index = (position as usize).min(stops.len() - 2);
+        let fraction = position - index as f64;
+
+        let channel = |stop: u32, shift: u32| ((stop >> shift) & 0xff) as f64;
+        let mix = |shift: u32| {
+            let (from, to) = (
+                channel(stops[index], shift),
+                channel(stops[index + 1], shift),
+            );
+            (from + (to - from) * fraction).round() as u8
+        };
+        Color::rgb(mix(16), mix(8), mix(0))
+    }
+}
+
+impl FromStr for ColorRamp {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "viridis" => Ok(ColorRamp::Viridis),
+            "magma" => Ok(ColorRamp::Magma),
+            "inferno" => Ok(ColorRamp::Inferno),
+            "plasma" => Ok(ColorRamp::Plasma),
+            "grayscale" | "greyscale" => Ok(ColorRamp::Grayscale),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown color ramp '{}' (expected viridis, magma, inferno, plasma or grayscale)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Converts a hue, saturation and value, each in `[0, 1]`, to an opaque colour.
+fn hsv(hue: f64, saturation: f64, value: f64) -> Color {
+    let sector = hue * 6.0;
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as usize {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+    let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+    Color::rgb(channel(r), channel(g), channel(b))
+}
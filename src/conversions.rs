@@ -1,10 +1,64 @@
+use crate::error::{GeoTiffError, Result};
+use gdal::raster::GdalType;
+use gdal_sys::GDALDataType;
+use std::str::FromStr;
+
 pub trait ConvertFromF64 {
+    /// Smallest value representable by the target type.
+    const MIN_VALUE: f64;
+    /// Largest value representable by the target type.
+    const MAX_VALUE: f64;
+    /// Whether the target type only holds whole numbers.
+    const IS_INTEGER: bool;
+    /// Whether the target type has an imaginary part.
+    const IS_COMPLEX: bool = false;
+
     /// Converts an f64 value to the target type T.
     /// Returns a default value if the conversion is out of bounds.
     fn convert_from_f64(v: f64) -> Self;
+
+    /// Converts a real and an imaginary part to the target type.
+    ///
+    /// Types without an imaginary part ignore `im`.
+    fn convert_from_parts(re: f64, im: f64) -> Self
+    where
+        Self: Sized,
+    {
+        let _ = im;
+        Self::convert_from_f64(re)
+    }
+
+    /// The value [`convert_from_f64`](Self::convert_from_f64) stores for
+    /// `v`, as an f64.
+    fn stored_value(v: f64) -> f64 {
+        if !Self::IS_INTEGER {
+            v
+        } else if v.is_nan() {
+            Self::MAX_VALUE
+        } else {
+            v.trunc().clamp(Self::MIN_VALUE, Self::MAX_VALUE)
+        }
+    }
+
+    /// Returns `true` if `v` can be stored in the target type as-is.
+    ///
+    /// Integer types accept finite whole numbers within their range, while
+    /// floating point types also accept NaN and the infinities, which are
+    /// common nodata markers.
+    fn can_represent(v: f64) -> bool {
+        if Self::IS_INTEGER {
+            v.is_finite() && v.fract() == 0.0 && v >= Self::MIN_VALUE && v <= Self::MAX_VALUE
+        } else {
+            !v.is_finite() || (v >= Self::MIN_VALUE && v <= Self::MAX_VALUE)
+        }
+    }
 }
 
 impl ConvertFromF64 for u8 {
+    const MIN_VALUE: f64 = u8::MIN as f64;
+    const MAX_VALUE: f64 = u8::MAX as f64;
+    const IS_INTEGER: bool = true;
+
     fn convert_from_f64(v: f64) -> Self {
         if v.is_finite() && v >= 0.0 && v <= u8::MAX as f64 {
             v as u8
@@ -16,7 +70,27 @@ impl ConvertFromF64 for u8 {
     }
 }
 
+impl ConvertFromF64 for i8 {
+    const MIN_VALUE: f64 = i8::MIN as f64;
+    const MAX_VALUE: f64 = i8::MAX as f64;
+    const IS_INTEGER: bool = true;
+
+    fn convert_from_f64(v: f64) -> Self {
+        if v.is_finite() && v >= i8::MIN as f64 && v <= i8::MAX as f64 {
+            v as i8
+        } else if v < i8::MIN as f64 {
+            i8::MIN
+        } else {
+            i8::MAX
+        }
+    }
+}
+
 impl ConvertFromF64 for u16 {
+    const MIN_VALUE: f64 = u16::MIN as f64;
+    const MAX_VALUE: f64 = u16::MAX as f64;
+    const IS_INTEGER: bool = true;
+
     fn convert_from_f64(v: f64) -> Self {
         if v.is_finite() && v >= 0.0 && v <= u16::MAX as f64 {
             v as u16
@@ -29,6 +103,10 @@ impl ConvertFromF64 for u16 {
 }
 
 impl ConvertFromF64 for i16 {
+    const MIN_VALUE: f64 = i16::MIN as f64;
+    const MAX_VALUE: f64 = i16::MAX as f64;
+    const IS_INTEGER: bool = true;
+
     fn convert_from_f64(v: f64) -> Self {
         if v.is_finite() && v >= i16::MIN as f64 && v <= i16::MAX as f64 {
             v as i16
@@ -41,6 +119,10 @@ impl ConvertFromF64 for i16 {
 }
 
 impl ConvertFromF64 for u32 {
+    const MIN_VALUE: f64 = u32::MIN as f64;
+    const MAX_VALUE: f64 = u32::MAX as f64;
+    const IS_INTEGER: bool = true;
+
     fn convert_from_f64(v: f64) -> Self {
         if v.is_finite() && v >= 0.0 && v <= u32::MAX as f64 {
             v as u32
@@ -53,6 +135,10 @@ impl ConvertFromF64 for u32 {
 }
 
 impl ConvertFromF64 for i32 {
+    const MIN_VALUE: f64 = i32::MIN as f64;
+    const MAX_VALUE: f64 = i32::MAX as f64;
+    const IS_INTEGER: bool = true;
+
     fn convert_from_f64(v: f64) -> Self {
         if v.is_finite() && v >= i32::MIN as f64 && v <= i32::MAX as f64 {
             v as i32
@@ -64,18 +150,219 @@ impl ConvertFromF64 for i32 {
     }
 }
 
-impl ConvertFromF64 for f32 {
+// `u64::MAX` and `i64::MAX` round up to the next power of two as f64, which is
+// out of range, hence the exclusive upper bounds below.
+
+impl ConvertFromF64 for u64 {
+    const MIN_VALUE: f64 = u64::MIN as f64;
+    const MAX_VALUE: f64 = u64::MAX as f64;
+    const IS_INTEGER: bool = true;
+
+    fn convert_from_f64(v: f64) -> Self {
+        if v.is_finite() && v >= 0.0 && v < u64::MAX as f64 {
+            v as u64
+        } else if v < 0.0 {
+            0
+        } else {
+            u64::MAX
+        }
+    }
+
+    fn can_represent(v: f64) -> bool {
+        v.is_finite() && v.fract() == 0.0 && v >= 0.0 && v < u64::MAX as f64
+    }
+}
+
+impl ConvertFromF64 for i64 {
+    const MIN_VALUE: f64 = i64::MIN as f64;
+    const MAX_VALUE: f64 = i64::MAX as f64;
+    const IS_INTEGER: bool = true;
+
     fn convert_from_f64(v: f64) -> Self {
-        if v.is_finite() {
-            v as f32
+        if v.is_finite() && v >= i64::MIN as f64 && v < i64::MAX as f64 {
+            v as i64
+        } else if v < i64::MIN as f64 {
+            i64::MIN
         } else {
-            f32::NAN
+            i64::MAX
         }
     }
+
+    fn can_represent(v: f64) -> bool {
+        v.is_finite() && v.fract() == 0.0 && v >= i64::MIN as f64 && v < i64::MAX as f64
+    }
+}
+
+impl ConvertFromF64 for f32 {
+    const MIN_VALUE: f64 = f32::MIN as f64;
+    const MAX_VALUE: f64 = f32::MAX as f64;
+    const IS_INTEGER: bool = false;
+
+    /// Keeps NaN and the infinities, which are valid nodata values, and
+    /// rounds finite values beyond the f32 range to the infinities.
+    fn convert_from_f64(v: f64) -> Self {
+        v as f32
+    }
+
+    fn stored_value(v: f64) -> f64 {
+        Self::convert_from_f64(v) as f64
+    }
 }
 
 impl ConvertFromF64 for f64 {
+    const MIN_VALUE: f64 = f64::MIN;
+    const MAX_VALUE: f64 = f64::MAX;
+    const IS_INTEGER: bool = false;
+
     fn convert_from_f64(v: f64) -> Self {
         v
     }
 }
+
+/// A complex pixel value, laid out as GDAL expects for the complex data types.
+///
+/// `Complex<i16>`, `Complex<i32>`, `Complex<f32>` and `Complex<f64>` map to
+/// GDAL's `CInt16`, `CInt32`, `CFloat32` and `CFloat64`. The real part comes
+/// from the builder's data generator and the imaginary part from its
+/// [`imaginary_generator`](crate::FakeGeoTiffBuilder::imaginary_generator).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+impl GdalType for Complex<i16> {
+    fn gdal_ordinal() -> GDALDataType::Type {
+        GDALDataType::GDT_CInt16
+    }
+}
+
+impl GdalType for Complex<i32> {
+    fn gdal_ordinal() -> GDALDataType::Type {
+        GDALDataType::GDT_CInt32
+    }
+}
+
+impl GdalType for Complex<f32> {
+    fn gdal_ordinal() -> GDALDataType::Type {
+        GDALDataType::GDT_CFloat32
+    }
+}
+
+impl GdalType for Complex<f64> {
+    fn gdal_ordinal() -> GDALDataType::Type {
+        GDALDataType::GDT_CFloat64
+    }
+}
+
+impl<T: ConvertFromF64 + Default> ConvertFromF64 for Complex<T> {
+    const MIN_VALUE: f64 = T::MIN_VALUE;
+    const MAX_VALUE: f64 = T::MAX_VALUE;
+    const IS_INTEGER: bool = T::IS_INTEGER;
+    const IS_COMPLEX: bool = true;
+
+    fn convert_from_f64(v: f64) -> Self {
+        Complex {
+            re: T::convert_from_f64(v),
+            im: T::default(),
+        }
+    }
+
+    fn convert_from_parts(re: f64, im: f64) -> Self {
+        Complex {
+            re: T::convert_from_f64(re),
+            im: T::convert_from_f64(im),
+        }
+    }
+
+    fn can_represent(v: f64) -> bool {
+        T::can_represent(v)
+    }
+}
+
+/// How generated values that don't fit the target data type are converted.
+///
+/// Every policy but [`Saturate`](Self::Saturate) rounds to the nearest
+/// whole number for integer types; floating point types are only affected
+/// by [`Error`](Self::Error).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConversionPolicy {
+    /// Clamp to the type's range and truncate towards zero.
+    #[default]
+    Saturate,
+    /// Round to the nearest whole number, then clamp to the type's range.
+    Round,
+    /// Round, then wrap around the type's range like integer overflow.
+    Wrap,
+    /// Round, and fail with [`GeoTiffError::ValueOutOfRange`] on overflow.
+    Error,
+    /// Linearly map the observed range of the generated values onto the
+    /// integer type's range, then round. This generates the data twice.
+    ///
+    /// A constant raster has no range to map, so its values go to the middle
+    /// of the type's range, and NaN and infinite values to its bottom.
+    /// Floating point types keep their values, as under
+    /// [`Saturate`](Self::Saturate).
+    Rescale,
+}
+
+impl ConversionPolicy {
+    /// Prepares `v` for [`ConvertFromF64::convert_from_f64`], which then stores
+    /// it without further loss.
+    ///
+    /// `range` is the observed range of the generated values, only used by
+    /// [`Rescale`](Self::Rescale). Returns `None` if the policy is
+    /// [`Error`](Self::Error) and `v` is out of range.
+    pub fn prepare<T: ConvertFromF64>(self, v: f64, range: (f64, f64)) -> Option<f64> {
+        if !T::IS_INTEGER {
+            return match self {
+                ConversionPolicy::Error if !T::can_represent(v) => None,
+                _ => Some(v),
+            };
+        }
+
+        match self {
+            ConversionPolicy::Saturate => Some(v),
+            ConversionPolicy::Round => Some(v.round()),
+            ConversionPolicy::Wrap => {
+                let v = v.round();
+                if !v.is_finite() || T::can_represent(v) {
+                    return Some(v);
+                }
+                let span = T::MAX_VALUE - T::MIN_VALUE + 1.0;
+                Some((v - T::MIN_VALUE).rem_euclid(span) + T::MIN_VALUE)
+            }
+            ConversionPolicy::Error => Some(v.round()).filter(|&v| T::can_represent(v)),
+            ConversionPolicy::Rescale => {
+                let (min, max) = range;
+                if !v.is_finite() {
+                    return Some(T::MIN_VALUE);
+                }
+                if max <= min {
+                    return Some(((T::MIN_VALUE + T::MAX_VALUE) / 2.0).round());
+                }
+                let scaled = (v - min) / (max - min) * (T::MAX_VALUE - T::MIN_VALUE);
+                Some((T::MIN_VALUE + scaled).round())
+            }
+        }
+    }
+}
+
+impl FromStr for ConversionPolicy {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "saturate" => Ok(ConversionPolicy::Saturate),
+            "round" => Ok(ConversionPolicy::Round),
+            "wrap" => Ok(ConversionPolicy::Wrap),
+            "error" => Ok(ConversionPolicy::Error),
+            "rescale" => Ok(ConversionPolicy::Rescale),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown conversion policy '{}' (expected saturate, round, wrap, error or rescale)",
+                s
+            ))),
+        }
+    }
+}
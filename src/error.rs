@@ -10,6 +10,27 @@ pub enum GeoTiffError {
     MissingField(String),
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
+    #[error("Invalid block size: {0}")]
+    InvalidBlockSize(String),
+    #[error("Value out of range: {0}")]
+    ValueOutOfRange(String),
+    #[error("Invalid cloud optimized GeoTIFF: {0}")]
+    InvalidCog(String),
 }
 
 pub type Result<T> = std::result::Result<T, GeoTiffError>;
+
+/// The last error reported by GDAL, for `gdal_sys` calls that the `gdal`
+/// crate doesn't wrap.
+pub(crate) fn last_gdal_error(class: gdal_sys::CPLErr::Type) -> GeoTiffError {
+    let (number, msg) = unsafe {
+        let msg = std::ffi::CStr::from_ptr(gdal_sys::CPLGetLastErrorMsg());
+        let error = (
+            gdal_sys::CPLGetLastErrorNo(),
+            msg.to_string_lossy().into_owned(),
+        );
+        gdal_sys::CPLErrorReset();
+        error
+    };
+    GeoTiffError::Gdal(gdal::errors::GdalError::CplError { class, number, msg })
+}
@@ -0,0 +1,514 @@
+use crate::error::{GeoTiffError, Result};
+use crate::patterns::{DataGenerator, PixelContext, RasterInfo};
+
+/// A pattern defined by an arithmetic expression, evaluated for every pixel.
+///
+/// # Syntax
+///
+/// * Numbers such as `2`, `0.5` and `1e-3`
+/// * Variables: `x` and `y` (pixel column and row), `band` (zero-based),
+///   `map_x` and `map_y` (map coordinates of the pixel centre), `width`,
+///   `height`, `bands`, and the constants `pi` and `e`. Through
+///   [`generate`](DataGenerator::generate), which has no raster, the raster
+///   variables are those of the default 256x256 raster
+/// * Operators, from lowest to highest precedence: comparisons (`<`, `<=`,
+///   `>`, `>=`, `==`, `!=`, giving 1 or 0), `+` and `-`, `*`, `/` and `%`,
+///   unary `-`, and `^` for powers
+/// * Functions: `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `atan2(y, x)`,
+///   `sinh`, `cosh`, `tanh`, `sqrt`, `abs`, `exp`, `ln` (or `log`), `log10`,
+///   `log2`, `floor`, `ceil`, `round`, `fract`, `sign`, `min(a, b)`,
+///   `max(a, b)`, `pow(a, b)`, `hypot(a, b)`, `clamp(v, min, max)` and
+///   `if(condition, then, else)`
+///
+/// # Examples
+///
+/// ```
+/// use rasterfakers::{DataGenerator, ExpressionPattern};
+///
+/// let pattern = ExpressionPattern::parse("sin(x / 10) * cos(y / 10) * 100 + band").unwrap();
+/// assert_eq!(pattern.generate(0, 0, 2), 2.0);
+///
+/// assert!(ExpressionPattern::parse("sin(x / 10").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExpressionPattern {
+    root: Node,
+}
+
+impl ExpressionPattern {
+    /// Parses `source`, failing with [`GeoTiffError::InvalidParameter`] on
+    /// syntax errors, unknown names, or functions called with the wrong
+    /// number of arguments.
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            source,
+            tokens: &tokens,
+            pos: 0,
+        };
+        let root = parser.comparison()?;
+        match parser.peek() {
+            None => Ok(Self { root }),
+            Some(_) => Err(parser.error("unexpected")),
+        }
+    }
+}
+
+impl DataGenerator for ExpressionPattern {
+    /// Assumes the default 256x256 raster, see [`RasterInfo::default`], so
+    /// `width`, `height`, `bands`, `map_x` and `map_y` describe that raster.
+    /// Only [`generate_at`](DataGenerator::generate_at) and
+    /// [`generate_block`](DataGenerator::generate_block) see the real one.
+    fn generate(&self, x: usize, y: usize, band: usize) -> f64 {
+        self.generate_at(&PixelContext::new(x, y, band, &RasterInfo::default()))
+    }
+
+    fn generate_at(&self, ctx: &PixelContext) -> f64 {
+        self.root.eval(ctx)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(usize, usize),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+struct Spanned {
+    token: Token,
+    start: usize,
+}
+
+const OPERATORS: [&str; 13] = [
+    "<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "%", "^", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Spanned>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        let token = if c.is_ascii_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit() || c == b'.' {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                i += 1;
+            }
+            // Exponent, as in 1e-3
+            if i < bytes.len() && matches!(bytes[i], b'e' | b'E') {
+                let mut j = i + 1;
+                if j < bytes.len() && matches!(bytes[j], b'+' | b'-') {
+                    j += 1;
+                }
+                if j < bytes.len() && bytes[j].is_ascii_digit() {
+                    i = j;
+                    while i < bytes.len() && bytes[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text = &source[start..i];
+            Token::Number(
+                text.parse()
+                    .map_err(|_| invalid(source, start, &format!("invalid number '{}'", text)))?,
+            )
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            Token::Ident(start, i)
+        } else if c == b'(' {
+            i += 1;
+            Token::LParen
+        } else if c == b')' {
+            i += 1;
+            Token::RParen
+        } else if c == b',' {
+            i += 1;
+            Token::Comma
+        } else {
+            match OPERATORS.iter().find(|op| source[i..].starts_with(*op)) {
+                // A lone '=' is almost certainly a mistyped '=='
+                Some(&"=") | None => {
+                    let c = source[i..].chars().next().unwrap_or_default();
+                    return Err(invalid(source, start, &format!("unexpected '{}'", c)));
+                }
+                Some(op) => {
+                    i += op.len();
+                    Token::Op(op)
+                }
+            }
+        };
+        tokens.push(Spanned { token, start });
+    }
+    Ok(tokens)
+}
+
+fn invalid(source: &str, pos: usize, message: &str) -> GeoTiffError {
+    GeoTiffError::InvalidParameter(format!(
+        "Invalid expression '{}': {} at position {}",
+        source, message, pos
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Var {
+    X,
+    Y,
+    Band,
+    MapX,
+    MapY,
+    Width,
+    Height,
+    Bands,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Sinh,
+    Cosh,
+    Tanh,
+    Sqrt,
+    Abs,
+    Exp,
+    Ln,
+    Log10,
+    Log2,
+    Floor,
+    Ceil,
+    Round,
+    Fract,
+    Sign,
+    Min,
+    Max,
+    Pow,
+    Hypot,
+    Clamp,
+    If,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sin" => Func::Sin,
+            "cos" => Func::Cos,
+            "tan" => Func::Tan,
+            "asin" => Func::Asin,
+            "acos" => Func::Acos,
+            "atan" => Func::Atan,
+            "atan2" => Func::Atan2,
+            "sinh" => Func::Sinh,
+            "cosh" => Func::Cosh,
+            "tanh" => Func::Tanh,
+            "sqrt" => Func::Sqrt,
+            "abs" => Func::Abs,
+            "exp" => Func::Exp,
+            "ln" | "log" => Func::Ln,
+            "log10" => Func::Log10,
+            "log2" => Func::Log2,
+            "floor" => Func::Floor,
+            "ceil" => Func::Ceil,
+            "round" => Func::Round,
+            "fract" => Func::Fract,
+            "sign" => Func::Sign,
+            "min" => Func::Min,
+            "max" => Func::Max,
+            "pow" => Func::Pow,
+            "hypot" => Func::Hypot,
+            "clamp" => Func::Clamp,
+            "if" => Func::If,
+            _ => return None,
+        })
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Func::Atan2 | Func::Min | Func::Max | Func::Pow | Func::Hypot => 2,
+            Func::Clamp | Func::If => 3,
+            _ => 1,
+        }
+    }
+
+    fn apply(self, args: &[f64]) -> f64 {
+        let a = args[0];
+        match self {
+            Func::Sin => a.sin(),
+            Func::Cos => a.cos(),
+            Func::Tan => a.tan(),
+            Func::Asin => a.asin(),
+            Func::Acos => a.acos(),
+            Func::Atan => a.atan(),
+            Func::Atan2 => a.atan2(args[1]),
+            Func::Sinh => a.sinh(),
+            Func::Cosh => a.cosh(),
+            Func::Tanh => a.tanh(),
+            Func::Sqrt => a.sqrt(),
+            Func::Abs => a.abs(),
+            Func::Exp => a.exp(),
+            Func::Ln => a.ln(),
+            Func::Log10 => a.log10(),
+            Func::Log2 => a.log2(),
+            Func::Floor => a.floor(),
+            Func::Ceil => a.ceil(),
+            Func::Round => a.round(),
+            Func::Fract => a.fract(),
+            Func::Sign => {
+                if a == 0.0 {
+                    0.0
+                } else {
+                    a.signum()
+                }
+            }
+            Func::Min => a.min(args[1]),
+            Func::Max => a.max(args[1]),
+            Func::Pow => a.powf(args[1]),
+            Func::Hypot => a.hypot(args[1]),
+            Func::Clamp => a.max(args[1]).min(args[2]),
+            Func::If => {
+                if a != 0.0 {
+                    args[1]
+                } else {
+                    args[2]
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Number(f64),
+    Var(Var),
+    Neg(Box<Node>),
+    Binary(&'static str, Box<Node>, Box<Node>),
+    Call(Func, Vec<Node>),
+}
+
+impl Node {
+    fn eval(&self, ctx: &PixelContext) -> f64 {
+        match self {
+            Node::Number(v) => *v,
+            Node::Var(var) => match var {
+                Var::X => ctx.x as f64,
+                Var::Y => ctx.y as f64,
+                Var::Band => ctx.band as f64,
+                Var::MapX => ctx.map_x,
+                Var::MapY => ctx.map_y,
+                Var::Width => ctx.raster.width as f64,
+                Var::Height => ctx.raster.height as f64,
+                Var::Bands => ctx.raster.bands as f64,
+            },
+            Node::Neg(node) => -node.eval(ctx),
+            Node::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(ctx), rhs.eval(ctx));
+                let truth = |condition: bool| if condition { 1.0 } else { 0.0 };
+                match *op {
+                    "+" => a + b,
+                    "-" => a - b,
+                    "*" => a * b,
+                    "/" => a / b,
+                    "%" => a % b,
+                    "^" => a.powf(b),
+                    "<" => truth(a < b),
+                    "<=" => truth(a <= b),
+                    ">" => truth(a > b),
+                    ">=" => truth(a >= b),
+                    "==" => truth(a == b),
+                    "!=" => truth(a != b),
+                    _ => unreachable!("unknown operator {}", op),
+                }
+            }
+            Node::Call(func, args) => {
+                let values: Vec<f64> = args.iter().map(|arg| arg.eval(ctx)).collect();
+                func.apply(&values)
+            }
+        }
+    }
+}
+
+/// A recursive descent parser, one method per precedence level.
+struct Parser<'a> {
+    source: &'a str,
+    tokens: &'a [Spanned],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).map(|spanned| spanned.token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    /// An error at the current token, e.g. "unexpected ')' at position 4".
+    fn error(&self, message: &str) -> GeoTiffError {
+        match self.tokens.get(self.pos) {
+            Some(spanned) => {
+                let end = self
+                    .tokens
+                    .get(self.pos + 1)
+                    .map_or(self.source.len(), |next| next.start);
+                let text = self.source[spanned.start..end].trim();
+                invalid(
+                    self.source,
+                    spanned.start,
+                    &format!("{} '{}'", message, text),
+                )
+            }
+            None => invalid(
+                self.source,
+                self.source.len(),
+                &format!("{} end of expression", message),
+            ),
+        }
+    }
+
+    fn binary_level(
+        &mut self,
+        operators: &[&str],
+        operand: fn(&mut Self) -> Result<Node>,
+    ) -> Result<Node> {
+        let mut node = operand(self)?;
+        while let Some(Token::Op(op)) = self.peek() {
+            if !operators.contains(&op) {
+                break;
+            }
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(operand(self)?));
+        }
+        Ok(node)
+    }
+
+    fn comparison(&mut self) -> Result<Node> {
+        self.binary_level(&["<", "<=", ">", ">=", "==", "!="], Self::additive)
+    }
+
+    fn additive(&mut self) -> Result<Node> {
+        self.binary_level(&["+", "-"], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Node> {
+        self.binary_level(&["*", "/", "%"], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        match self.peek() {
+            Some(Token::Op("-")) => {
+                self.pos += 1;
+                Ok(Node::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Op("+")) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// Powers are right associative, and bind tighter than unary minus on
+    /// their left: `-2^2` is -4, but `2^-1` is 0.5.
+    fn power(&mut self) -> Result<Node> {
+        let base = self.primary()?;
+        if self.peek() == Some(Token::Op("^")) {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(Node::Binary("^", Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node> {
+        match self.peek() {
+            Some(Token::Number(v)) => {
+                self.pos += 1;
+                Ok(Node::Number(v))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let node = self.comparison()?;
+                self.expect(Token::RParen, "expected ')' but found")?;
+                Ok(node)
+            }
+            Some(Token::Ident(start, end)) => {
+                let name = &self.source[start..end];
+                if self.tokens.get(self.pos + 1).map(|t| t.token) == Some(Token::LParen) {
+                    self.call(name)
+                } else {
+                    let node = match name {
+                        "x" => Node::Var(Var::X),
+                        "y" => Node::Var(Var::Y),
+                        "band" => Node::Var(Var::Band),
+                        "map_x" => Node::Var(Var::MapX),
+                        "map_y" => Node::Var(Var::MapY),
+                        "width" => Node::Var(Var::Width),
+                        "height" => Node::Var(Var::Height),
+                        "bands" => Node::Var(Var::Bands),
+                        "pi" => Node::Number(std::f64::consts::PI),
+                        "e" => Node::Number(std::f64::consts::E),
+                        _ => return Err(self.error("unknown variable")),
+                    };
+                    self.pos += 1;
+                    Ok(node)
+                }
+            }
+            _ => Err(self.error("unexpected")),
+        }
+    }
+
+    fn call(&mut self, name: &str) -> Result<Node> {
+        let func = Func::from_name(name).ok_or_else(|| self.error("unknown function"))?;
+        let start = self.tokens[self.pos].start;
+        self.pos += 2;
+
+        let mut args = Vec::new();
+        if self.peek() != Some(Token::RParen) {
+            args.push(self.comparison()?);
+            while self.peek() == Some(Token::Comma) {
+                self.pos += 1;
+                args.push(self.comparison()?);
+            }
+        }
+        self.expect(Token::RParen, "expected ',' or ')' but found")?;
+
+        if args.len() != func.arity() {
+            return Err(invalid(
+                self.source,
+                start,
+                &format!(
+                    "{}() takes {} argument(s) but was given {}",
+                    name,
+                    func.arity(),
+                    args.len()
+                ),
+            ));
+        }
+        Ok(Node::Call(func, args))
+    }
+
+    fn expect(&mut self, token: Token, message: &str) -> Result<()> {
+        if self.peek() == Some(token) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+}
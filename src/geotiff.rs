@@ -1,11 +1,21 @@
-use crate::conversions::ConvertFromF64;
-use crate::error::{GeoTiffError, Result};
-use crate::patterns::DataGenerator;
+use crate::cog::validate_cog;
+use crate::colors::ColorTable;
+use crate::conversions::{ConversionPolicy, ConvertFromF64};
+use crate::error::{last_gdal_error, GeoTiffError, Result};
+use crate::options::{
+    BigTiff, Compression, Interleave, Layout, OverviewStorage, Overviews, Predictor, Resampling,
+};
+use crate::patterns::{
+    ConstantPattern, DataGenerator, HoleMask, NoDataHoles, PerBand, RasterInfo, Window,
+};
+use crate::rat::{PixelCounter, RasterAttributeTable};
+use gdal::cpl::CslStringList;
 use gdal::raster::Buffer;
-use gdal::raster::GdalType;
-use gdal::raster::RasterCreationOptions;
-use gdal::DriverManager;
-use std::path::PathBuf;
+use gdal::raster::{ColorInterpretation, GdalDataType, GdalType};
+use gdal::raster::{RasterBand, RasterCreationOptions};
+use gdal::{Dataset, DriverManager};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct GeoTransform {
@@ -30,6 +40,34 @@ impl Default for GeoTransform {
     }
 }
 
+impl GeoTransform {
+    /// Converts fractional pixel coordinates into map coordinates.
+    ///
+    /// Integer coordinates address pixel corners; add 0.5 to get the centre.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::GeoTransform;
+    ///
+    /// let transform = GeoTransform {
+    ///     x_min: 10.0,
+    ///     pixel_width: 0.5,
+    ///     y_max: 50.0,
+    ///     pixel_height: -0.5,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(transform.pixel_to_map(4.0, 2.0), (12.0, 49.0));
+    /// ```
+    pub fn pixel_to_map(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.x_min + x * self.pixel_width + y * self.rotation_x,
+            self.y_max + x * self.rotation_y + y * self.pixel_height,
+        )
+    }
+}
+
 impl From<GeoTransform> for [f64; 6] {
     fn from(transform: GeoTransform) -> Self {
         [
@@ -54,7 +92,23 @@ where
     geotransform: Option<GeoTransform>,
     output_path: PathBuf,
     data_generator: Box<dyn DataGenerator>,
+    imaginary_generator: Option<Box<dyn DataGenerator>>,
     cloud_optimized: bool,
+    nodata: Vec<Option<f64>>,
+    color_table: Option<ColorTable>,
+    category_names: BTreeMap<usize, BTreeMap<usize, String>>,
+    attribute_tables: BTreeMap<usize, RasterAttributeTable>,
+    bigtiff: Option<BigTiff>,
+    compression: Option<Compression>,
+    compression_level: Option<u32>,
+    predictor: Option<Predictor>,
+    layout: Option<Layout>,
+    interleave: Option<Interleave>,
+    conversion: ConversionPolicy,
+    overviews: Vec<usize>,
+    resampling: Resampling,
+    overview_storage: OverviewStorage,
+    threads: usize,
     _phantom: std::marker::PhantomData<T>,
 }
 
@@ -111,7 +165,26 @@ pub struct FakeGeoTiffBuilder {
     geotransform: Option<GeoTransform>,
     output_path: Option<PathBuf>,
     data_generator: Option<Box<dyn DataGenerator>>,
+    band_generators: BTreeMap<usize, Box<dyn DataGenerator>>,
+    imaginary_generator: Option<Box<dyn DataGenerator>>,
     cloud_optimized: bool,
+    nodata: Option<f64>,
+    band_nodata: BTreeMap<usize, f64>,
+    nodata_holes: Option<(HoleMask, u64)>,
+    color_table: Option<ColorTable>,
+    category_names: BTreeMap<usize, BTreeMap<usize, String>>,
+    attribute_tables: BTreeMap<usize, RasterAttributeTable>,
+    bigtiff: Option<BigTiff>,
+    compression: Option<Compression>,
+    compression_level: Option<u32>,
+    predictor: Option<Predictor>,
+    layout: Option<Layout>,
+    interleave: Option<Interleave>,
+    conversion: ConversionPolicy,
+    overviews: Option<Overviews>,
+    resampling: Resampling,
+    overview_storage: OverviewStorage,
+    threads: usize,
 }
 
 impl Default for FakeGeoTiffBuilder {
@@ -124,7 +197,26 @@ impl Default for FakeGeoTiffBuilder {
             geotransform: Some(GeoTransform::default()),
             output_path: None,
             data_generator: None,
+            band_generators: BTreeMap::new(),
+            imaginary_generator: None,
             cloud_optimized: false,
+            nodata: None,
+            band_nodata: BTreeMap::new(),
+            nodata_holes: None,
+            color_table: None,
+            category_names: BTreeMap::new(),
+            attribute_tables: BTreeMap::new(),
+            bigtiff: None,
+            compression: None,
+            compression_level: None,
+            predictor: None,
+            layout: None,
+            interleave: None,
+            conversion: ConversionPolicy::Saturate,
+            overviews: None,
+            resampling: Resampling::Nearest,
+            overview_storage: OverviewStorage::Internal,
+            threads: 1,
         }
     }
 }
@@ -184,21 +276,414 @@ impl FakeGeoTiffBuilder {
         self
     }
 
+    /// Sets a closure taking `(x, y, band)` as the data generator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::FakeGeoTiffBuilder;
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .data_fn(|x, y, band| ((x / 16 + y / 16 + band) % 2) as f64 * 255.0)
+    ///     .output_path("checkerboard.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn data_fn<F>(self, f: F) -> Self
+    where
+        F: Fn(usize, usize, usize) -> f64 + Send + Sync + 'static,
+    {
+        self.data_generator(Box::new(f))
+    }
+
+    /// Sets the data generator of a single band, overriding
+    /// [`data_generator`](Self::data_generator) for that band.
+    ///
+    /// `band` is zero-based. The generator still receives the real band index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{FakeGeoTiffBuilder, NoisePattern, PerlinNoise, SineWavePattern};
+    ///
+    /// // Gradient red band, then one generator per band for green, blue and NIR
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .bands(4).unwrap()
+    ///     .band_generator(1, Box::new(NoisePattern))
+    ///     .band_generator(2, Box::new(SineWavePattern))
+    ///     .band_generator(3, Box::new(PerlinNoise::new(0.01)))
+    ///     .output_path("rgbn.tiff")
+    ///     .build::<u16>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn band_generator(mut self, band: usize, generator: Box<dyn DataGenerator>) -> Self {
+        self.band_generators.insert(band, generator);
+        self
+    }
+
+    /// Sets the generator of the imaginary part of complex data types.
+    ///
+    /// The real part comes from the [`data_generator`](Self::data_generator).
+    /// Without an imaginary generator, the imaginary part is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::conversions::Complex;
+    /// use rasterfakers::{FakeGeoTiffBuilder, GaussianNoise};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .data_generator(Box::new(GaussianNoise::new(0.0, 1.0).seed(1)))
+    ///     .imaginary_generator(Box::new(GaussianNoise::new(0.0, 1.0).seed(2)))
+    ///     .output_path("slc.tiff")
+    ///     .build::<Complex<f32>>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn imaginary_generator(mut self, generator: Box<dyn DataGenerator>) -> Self {
+        self.imaginary_generator = Some(generator);
+        self
+    }
+
+    /// Sets how generated values that don't fit the data type are converted.
+    ///
+    /// Defaults to [`ConversionPolicy::Saturate`]. Nodata values are never
+    /// converted, and don't count towards the range observed by
+    /// [`ConversionPolicy::Rescale`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::conversions::ConversionPolicy;
+    /// use rasterfakers::{FakeGeoTiffBuilder, NoisePattern};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .data_generator(Box::new(NoisePattern))
+    ///     .conversion_policy(ConversionPolicy::Rescale)
+    ///     .output_path("rescaled.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn conversion_policy(mut self, policy: ConversionPolicy) -> Self {
+        self.conversion = policy;
+        self
+    }
+
+    /// Sets whether the output is a cloud optimized GeoTIFF.
+    ///
+    /// COGs are written with GDAL's COG driver, with overviews, and checked
+    /// with [`validate_cog`] before [`FakeGeoTiff::write`] returns.
     pub fn cloud_optimized(mut self, cloud_optimized: bool) -> Self {
         self.cloud_optimized = cloud_optimized;
         self
     }
 
+    /// Sets the nodata value written to every band.
+    ///
+    /// The value is validated against the target data type in [`build`](Self::build).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::FakeGeoTiffBuilder;
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .nodata(-9999.0)
+    ///     .output_path("nodata.tiff")
+    ///     .build::<i16>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn nodata(mut self, value: f64) -> Self {
+        self.nodata = Some(value);
+        self
+    }
+
+    /// Sets the nodata value of a single band, overriding [`nodata`](Self::nodata).
+    ///
+    /// `band` is zero-based, matching the `band` argument passed to
+    /// [`DataGenerator::generate`].
+    pub fn band_nodata(mut self, band: usize, value: f64) -> Self {
+        self.band_nodata.insert(band, value);
+        self
+    }
+
+    /// Punches nodata holes into the generated data.
+    ///
+    /// The data generator is wrapped in a [`NoDataHoles`] that fills the
+    /// pixels selected by `mask` with each band's nodata value, so a nodata
+    /// value must be configured for every band. The same `seed` always
+    /// produces the same holes. Holes in complex data get the nodata value
+    /// in both the real and the imaginary part. A blob or tile `size` of 0
+    /// is treated as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{FakeGeoTiffBuilder, HoleMask};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .nodata(0.0)
+    ///     .nodata_holes(HoleMask::Blobs { fraction: 0.2, size: 32 }, 42)
+    ///     .output_path("holes.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn nodata_holes(mut self, mask: HoleMask, seed: u64) -> Self {
+        self.nodata_holes = Some((mask, seed));
+        self
+    }
+
+    /// Attaches a colour table to the band, and marks it as a palette
+    /// index band so that readers draw its pixels with the table's colours.
+    ///
+    /// GeoTIFFs only support colour tables on single band `u8` or `u16`
+    /// rasters, which [`build`](Self::build) checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::colors::{Color, ColorTable};
+    /// use rasterfakers::{CategoricalPattern, FakeGeoTiffBuilder};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .data_generator(Box::new(CategoricalPattern::new([(0.0, 1.0), (1.0, 1.0)]).unwrap()))
+    ///     .color_table(
+    ///         ColorTable::new()
+    ///             .color(0, Color::rgb(255, 255, 255))
+    ///             .color(1, Color::rgb(255, 0, 0)),
+    ///     )
+    ///     .output_path("mask.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn color_table(mut self, color_table: ColorTable) -> Self {
+        self.color_table = Some(color_table);
+        self
+    }
+
+    /// Sets the category names of a band, as `(value, name)` pairs.
+    ///
+    /// `band` is zero-based. Values without a name get an empty one, and
+    /// [`build`](Self::build) checks that every value fits the data type.
+    /// GDAL stores category names in the `.aux.xml` file next to the
+    /// GeoTIFF.
+    pub fn category_names<S: Into<String>>(
+        mut self,
+        band: usize,
+        names: impl IntoIterator<Item = (usize, S)>,
+    ) -> Self {
+        let names = names
+            .into_iter()
+            .map(|(value, name)| (value, name.into()))
+            .collect();
+        self.category_names.insert(band, names);
+        self
+    }
+
+    /// Attaches a raster attribute table to a band.
+    ///
+    /// `band` is zero-based. See [`RasterAttributeTable`] for an example.
+    pub fn attribute_table(mut self, band: usize, table: RasterAttributeTable) -> Self {
+        self.attribute_tables.insert(band, table);
+        self
+    }
+
+    /// Sets whether the output is written as BigTIFF.
+    ///
+    /// Data is generated and written one block at a time, so rasters far
+    /// larger than memory can be produced; past 4 GB they need BigTIFF.
+    /// Defaults to GDAL's own choice, or [`BigTiff::IfSafer`] for COGs.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rasterfakers::{BigTiff, ConstantPattern, FakeGeoTiffBuilder};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .dimensions(100_000, 100_000).unwrap()
+    ///     .data_generator(Box::new(ConstantPattern(1.0)))
+    ///     .bigtiff(BigTiff::Yes)
+    ///     .output_path("huge.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn bigtiff(mut self, bigtiff: BigTiff) -> Self {
+        self.bigtiff = Some(bigtiff);
+        self
+    }
+
+    /// Sets the compression codec.
+    ///
+    /// Defaults to no compression, or LZW for COGs. The codec is validated
+    /// against the data type in [`build`](Self::build): JPEG and WEBP only
+    /// support `u8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{Compression, FakeGeoTiffBuilder, Predictor};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .compression(Compression::Zstd)
+    ///     .compression_level(9)
+    ///     .predictor(Predictor::FloatingPoint)
+    ///     .output_path("zstd.tiff")
+    ///     .build::<f32>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Sets the compression level, or the quality for JPEG and WEBP.
+    ///
+    /// Only DEFLATE (1-12), ZSTD (1-22), LZMA (0-9), JPEG (1-100) and
+    /// WEBP (1-100) accept a level.
+    pub fn compression_level(mut self, level: u32) -> Self {
+        self.compression_level = Some(level);
+        self
+    }
+
+    /// Sets the predictor applied before compression.
+    ///
+    /// Only LZW, DEFLATE, ZSTD and LZMA support a predictor, and
+    /// [`Predictor::FloatingPoint`] requires a floating point data type.
+    pub fn predictor(mut self, predictor: Predictor) -> Self {
+        self.predictor = Some(predictor);
+        self
+    }
+
+    /// Sets the internal block layout.
+    ///
+    /// Defaults to GDAL's own choice of strips, or 512x512 tiles for COGs,
+    /// which must have square tiles. Tile sizes must be multiples of 16;
+    /// strips may have any number of rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{FakeGeoTiffBuilder, Layout};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .dimensions(2048, 2048).unwrap()
+    ///     .layout(Layout::Tiles { width: 512, height: 512 })
+    ///     .output_path("tiled.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Sets how the bands are interleaved.
+    ///
+    /// Defaults to GDAL's own choice, which is pixel interleaving for
+    /// multiband GeoTIFFs. COGs are always pixel interleaved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{FakeGeoTiffBuilder, Interleave};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .bands(4).unwrap()
+    ///     .interleave(Interleave::Band)
+    ///     .output_path("band_interleaved.tiff")
+    ///     .build::<u16>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn interleave(mut self, interleave: Interleave) -> Self {
+        self.interleave = Some(interleave);
+        self
+    }
+
+    /// Sets which overview levels are built.
+    ///
+    /// Defaults to no overviews, or [`Overviews::Auto`] down to 256 pixels
+    /// for COGs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{FakeGeoTiffBuilder, Overviews, Resampling};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .dimensions(4096, 4096).unwrap()
+    ///     .overviews(Overviews::Factors(vec![2, 4, 8]))
+    ///     .overview_resampling(Resampling::Average)
+    ///     .output_path("overviews.tiff")
+    ///     .build::<u8>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn overviews(mut self, overviews: Overviews) -> Self {
+        self.overviews = Some(overviews);
+        self
+    }
+
+    /// Sets the resampling method used to compute overviews. Defaults to
+    /// [`Resampling::Nearest`].
+    pub fn overview_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// Sets whether overviews are stored inside the GeoTIFF or in an external
+    /// `.ovr` file. COG overviews are always internal.
+    pub fn overview_storage(mut self, storage: OverviewStorage) -> Self {
+        self.overview_storage = storage;
+        self
+    }
+
+    /// Sets the number of threads used to generate pixel data.
+    ///
+    /// Blocks are generated concurrently and written in order, so the output
+    /// is byte-identical to single-threaded generation. More threads than
+    /// available cores only add overhead, so larger counts are capped at
+    /// the number of cores, and `0` uses every available core. Defaults to 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rasterfakers::{FakeGeoTiffBuilder, PerlinNoise};
+    ///
+    /// let geotiff = FakeGeoTiffBuilder::new()
+    ///     .dimensions(2048, 2048).unwrap()
+    ///     .data_generator(Box::new(PerlinNoise::new(0.01)))
+    ///     .threads(0)
+    ///     .output_path("parallel.tiff")
+    ///     .build::<f32>().unwrap();
+    ///
+    /// geotiff.write().unwrap();
+    /// ```
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
     /// Builds the `FakeGeoTiff` instance with the configured settings.
     ///
     /// # Type Parameters
     ///
     /// * `T` - The data type for the GeoTIFF. Must implement `GdalType`,
-    ///         `Default`, `Clone`, `ConvertFromF64`, `Copy`, `Send`, and `Sync`.
+    ///   `Default`, `Clone`, `ConvertFromF64`, `Copy`, `Send`, and `Sync`.
     ///
     /// # Errors
     ///
-    /// Returns an error if required fields are missing or invalid.
+    /// Returns an error if required fields are missing or invalid, or if a
+    /// nodata value cannot be represented by `T`.
     ///
     /// # Examples
     ///
@@ -219,6 +704,184 @@ impl FakeGeoTiffBuilder {
             .output_path
             .ok_or_else(|| GeoTiffError::MissingField("Output path must be specified".into()))?;
 
+        if let Some(&band) = self
+            .band_generators
+            .keys()
+            .find(|&&band| band >= self.bands)
+        {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Generator band index {} is out of range for {} band(s)",
+                band, self.bands
+            )));
+        }
+
+        if let Some(&band) = self.band_nodata.keys().find(|&&band| band >= self.bands) {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Nodata band index {} is out of range for {} band(s)",
+                band, self.bands
+            )));
+        }
+
+        let nodata: Vec<Option<f64>> = (0..self.bands)
+            .map(|band| self.band_nodata.get(&band).copied().or(self.nodata))
+            .collect();
+
+        if let Some(value) = nodata.iter().flatten().find(|&&v| !T::can_represent(v)) {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Nodata value {} cannot be represented by {}",
+                value,
+                std::any::type_name::<T>()
+            )));
+        }
+
+        if let Some(&band) = self.category_names.keys().find(|&&band| band >= self.bands) {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Category names band index {} is out of range for {} band(s)",
+                band, self.bands
+            )));
+        }
+        let max_category = self
+            .category_names
+            .values()
+            .filter_map(|names| names.keys().next_back())
+            .max();
+        if let Some(&value) = max_category.filter(|&&value| !T::can_represent(value as f64)) {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Category name value {} cannot be represented by {}",
+                value,
+                std::any::type_name::<T>()
+            )));
+        }
+
+        for (&band, table) in &self.attribute_tables {
+            if band >= self.bands {
+                return Err(GeoTiffError::InvalidParameter(format!(
+                    "Attribute table band index {} is out of range for {} band(s)",
+                    band, self.bands
+                )));
+            }
+            table.validate()?;
+            if table.counts_pixels() && T::IS_COMPLEX {
+                return Err(GeoTiffError::InvalidParameter(format!(
+                    "Pixel counts are not supported for complex data types like {}",
+                    std::any::type_name::<T>()
+                )));
+            }
+        }
+
+        if let Some(color_table) = &self.color_table {
+            validate_color_table::<T>(color_table, self.bands)?;
+        }
+
+        if self.imaginary_generator.is_some() && !T::IS_COMPLEX {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "An imaginary generator requires a complex data type, not {}",
+                std::any::type_name::<T>()
+            )));
+        }
+
+        if self.cloud_optimized && self.compression == Some(Compression::Packbits) {
+            return Err(GeoTiffError::InvalidParameter(
+                "PACKBITS compression is not supported for cloud optimized GeoTIFFs".into(),
+            ));
+        }
+
+        validate_compression::<T>(
+            self.compression.unwrap_or(if self.cloud_optimized {
+                Compression::Lzw
+            } else {
+                Compression::None
+            }),
+            self.compression_level,
+            self.predictor,
+        )?;
+
+        if let Some(layout) = self.layout {
+            layout.validate()?;
+            match layout {
+                Layout::Strips { .. } if self.cloud_optimized => {
+                    return Err(GeoTiffError::InvalidParameter(
+                        "Cloud optimized GeoTIFFs must be tiled".into(),
+                    ));
+                }
+                Layout::Tiles { width, height } if self.cloud_optimized && width != height => {
+                    return Err(GeoTiffError::InvalidBlockSize(format!(
+                        "Cloud optimized GeoTIFFs need square tiles, got {}x{}",
+                        width, height
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        let overviews = self.overviews.unwrap_or(if self.cloud_optimized {
+            Overviews::Auto { min_size: 256 }
+        } else {
+            Overviews::None
+        });
+        overviews.validate(self.width, self.height)?;
+        if self.cloud_optimized && self.overview_storage == OverviewStorage::External {
+            return Err(GeoTiffError::InvalidParameter(
+                "Cloud optimized GeoTIFFs need internal overviews".into(),
+            ));
+        }
+        // GDAL's COG driver only writes band interleaved files from GDAL 3.11,
+        // and silently falls back to pixel interleaving before that
+        if self.cloud_optimized && self.interleave == Some(Interleave::Band) {
+            return Err(GeoTiffError::InvalidParameter(
+                "Cloud optimized GeoTIFFs can't be band interleaved".into(),
+            ));
+        }
+
+        // More threads than cores only adds overhead
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let threads = match self.threads {
+            0 => cores,
+            threads => threads.min(cores),
+        };
+
+        let mut data_generator = self
+            .data_generator
+            .unwrap_or_else(|| Box::new(crate::patterns::GradientPattern));
+
+        if !self.band_generators.is_empty() {
+            data_generator = Box::new(self.band_generators.into_iter().fold(
+                PerBand::new(data_generator),
+                |per_band, (band, generator)| per_band.band(band, generator),
+            ));
+        }
+
+        let mut imaginary_generator = self.imaginary_generator;
+        if let Some((mask, seed)) = self.nodata_holes {
+            validate_hole_mask(&mask)?;
+            let values = nodata
+                .iter()
+                .copied()
+                .collect::<Option<Vec<f64>>>()
+                .ok_or_else(|| {
+                    GeoTiffError::MissingField(
+                        "Nodata value must be specified for every band to inject nodata holes"
+                            .into(),
+                    )
+                })?;
+            // Holes in complex data are nodata in both parts
+            if T::IS_COMPLEX {
+                let imaginary = imaginary_generator
+                    .take()
+                    .unwrap_or_else(|| Box::new(ConstantPattern(0.0)));
+                imaginary_generator = Some(Box::new(
+                    NoDataHoles::new(imaginary, mask.clone(), values[0])
+                        .band_nodata(values.clone())
+                        .seed(seed),
+                ));
+            }
+            data_generator = Box::new(
+                NoDataHoles::new(data_generator, mask, values[0])
+                    .band_nodata(values)
+                    .seed(seed),
+            );
+        }
+
         Ok(FakeGeoTiff {
             width: self.width,
             height: self.height,
@@ -226,52 +889,449 @@ impl FakeGeoTiffBuilder {
             projection: self.projection,
             geotransform: self.geotransform,
             output_path,
-            data_generator: self
-                .data_generator
-                .unwrap_or_else(|| Box::new(crate::patterns::GradientPattern)),
+            data_generator,
+            imaginary_generator,
             cloud_optimized: self.cloud_optimized,
+            nodata,
+            color_table: self.color_table,
+            category_names: self.category_names,
+            attribute_tables: self.attribute_tables,
+            bigtiff: self.bigtiff,
+            compression: self.compression,
+            compression_level: self.compression_level,
+            predictor: self.predictor,
+            layout: self.layout,
+            interleave: self.interleave,
+            conversion: self.conversion,
+            overviews: overviews.factors(self.width, self.height),
+            resampling: self.resampling,
+            overview_storage: self.overview_storage,
+            threads,
             _phantom: std::marker::PhantomData,
         })
     }
 }
 
+fn validate_color_table<T: GdalType>(color_table: &ColorTable, bands: usize) -> Result<()> {
+    let max_value = match T::datatype() {
+        GdalDataType::UInt8 => u8::MAX as u16,
+        GdalDataType::UInt16 => u16::MAX,
+        _ => {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Color tables require u8 or u16 data, not {}",
+                std::any::type_name::<T>()
+            )))
+        }
+    };
+    if bands != 1 {
+        return Err(GeoTiffError::InvalidParameter(format!(
+            "Color tables require a single band, not {}",
+            bands
+        )));
+    }
+    match color_table.max_value() {
+        Some(value) if value > max_value => Err(GeoTiffError::InvalidParameter(format!(
+            "Color table value {} cannot be represented by {}",
+            value,
+            std::any::type_name::<T>()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn validate_compression<T: GdalType + ConvertFromF64>(
+    compression: Compression,
+    level: Option<u32>,
+    predictor: Option<Predictor>,
+) -> Result<()> {
+    let type_name = std::any::type_name::<T>();
+
+    if matches!(compression, Compression::Jpeg | Compression::Webp)
+        && T::datatype() != GdalDataType::UInt8
+    {
+        return Err(GeoTiffError::InvalidParameter(format!(
+            "{} compression only supports u8 data, not {}",
+            compression.name(),
+            type_name
+        )));
+    }
+
+    if compression == Compression::Lerc && T::IS_COMPLEX {
+        return Err(GeoTiffError::InvalidParameter(format!(
+            "LERC compression does not support complex data, got {}",
+            type_name
+        )));
+    }
+
+    if let Some(level) = level {
+        match compression.level_range() {
+            Some((_, min, max)) if (min..=max).contains(&level) => {}
+            Some((_, min, max)) => {
+                return Err(GeoTiffError::InvalidParameter(format!(
+                    "{} compression level must be between {} and {}, got {}",
+                    compression.name(),
+                    min,
+                    max,
+                    level
+                )))
+            }
+            None => {
+                return Err(GeoTiffError::InvalidParameter(format!(
+                    "{} compression does not support a level",
+                    compression.name()
+                )))
+            }
+        }
+    }
+
+    match predictor {
+        Some(Predictor::None) | None => {}
+        Some(_) if !compression.supports_predictor() => {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "{} compression does not support a predictor",
+                compression.name()
+            )))
+        }
+        Some(_) if T::IS_COMPLEX => {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Predictors do not support complex data, got {}",
+                type_name
+            )))
+        }
+        Some(Predictor::FloatingPoint) if T::IS_INTEGER => {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Floating point predictor requires floating point data, not {}",
+                type_name
+            )))
+        }
+        Some(_) => {}
+    }
+
+    Ok(())
+}
+
+fn validate_hole_mask(mask: &HoleMask) -> Result<()> {
+    let fraction = match *mask {
+        HoleMask::Scattered { fraction }
+        | HoleMask::Rows { fraction }
+        | HoleMask::Blobs { fraction, .. }
+        | HoleMask::Tiles { fraction, .. } => fraction,
+        HoleMask::Rect { .. } => return Ok(()),
+    };
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(GeoTiffError::InvalidParameter(format!(
+            "Nodata hole fraction must be between 0 and 1, got {}",
+            fraction
+        )));
+    }
+    Ok(())
+}
+
 impl<T> FakeGeoTiff<T>
 where
     T: GdalType + Default + Clone + ConvertFromF64 + Copy + Send + Sync,
 {
-    fn generate_data(&self) -> Vec<T> {
-        let total_size = self.width * self.height * self.bands;
-        let mut data = Vec::with_capacity(total_size);
-
-        for band in 0..self.bands {
-            for y in 0..self.height {
-                for x in 0..self.width {
-                    let value = self.data_generator.generate(x, y, band);
-                    data.push(T::convert_from_f64(value));
+    fn raster_info(&self) -> RasterInfo {
+        RasterInfo {
+            width: self.width,
+            height: self.height,
+            bands: self.bands,
+            geotransform: self.geotransform.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Generates and writes the data block by block, following the
+    /// dataset's natural block layout so that memory use stays bounded by
+    /// the size of a few blocks, however large the raster is. The attribute
+    /// tables are written last, once their pixel counts are known.
+    fn write_data(&self, dataset: &Dataset) -> Result<()> {
+        let raster = self.raster_info();
+        let (block_width, block_height) = dataset.rasterband(1)?.block_size();
+
+        let mut windows = Vec::new();
+        for y_off in (0..self.height).step_by(block_height) {
+            for x_off in (0..self.width).step_by(block_width) {
+                for band in 0..self.bands {
+                    windows.push(Window {
+                        x_off,
+                        y_off,
+                        width: block_width.min(self.width - x_off),
+                        height: block_height.min(self.height - y_off),
+                        band,
+                    });
                 }
             }
         }
+        // Band interleaved files store each band's blocks together, so write
+        // them in that order
+        if self.interleave == Some(Interleave::Band) {
+            windows.sort_by_key(|window| window.band);
+        }
 
-        data
+        let range = match self.conversion {
+            ConversionPolicy::Rescale => self.observed_range(&raster, &windows),
+            _ => (0.0, 0.0),
+        };
+
+        let counters: BTreeMap<usize, PixelCounter> = self
+            .attribute_tables
+            .iter()
+            .filter_map(|(&band, table)| Some((band, PixelCounter::new(table)?)))
+            .collect();
+        let mut counts: BTreeMap<usize, Vec<u64>> = counters
+            .iter()
+            .map(|(&band, counter)| (band, vec![0; counter.rows()]))
+            .collect();
+
+        // Keep a few blocks per thread in flight, then write them in order
+        for batch in windows.chunks(self.threads.saturating_mul(4)) {
+            let blocks = self.map_windows(batch, |window| {
+                let counter = counters.get(&window.band);
+                self.generate_window(&raster, window, range, counter)
+            });
+            for (window, block) in batch.iter().zip(blocks) {
+                let (data, block_counts) = block?;
+                if let Some(block_counts) = block_counts {
+                    let band_counts = counts.get_mut(&window.band).expect("counted band");
+                    for (count, block_count) in band_counts.iter_mut().zip(block_counts) {
+                        *count += block_count;
+                    }
+                }
+                let mut buffer = Buffer::new((window.width, window.height), data);
+                dataset.rasterband(window.band + 1)?.write(
+                    (window.x_off as isize, window.y_off as isize),
+                    (window.width, window.height),
+                    &mut buffer,
+                )?;
+            }
+        }
+
+        for (&band, table) in &self.attribute_tables {
+            let band_counts = counts.get(&band).map(Vec::as_slice);
+            table.write(&dataset.rasterband(band + 1)?, band_counts)?;
+        }
+
+        Ok(())
     }
 
-    pub fn write(&self) -> Result<()> {
-        // Here we handle the cloud optimized part
-        let creation_options = if self.cloud_optimized {
-            vec![
-                "TILED=YES",
-                "COMPRESS=LZW",
-                "COPY_SRC_OVERVIEWS=YES",
-                "BIGTIFF=IF_SAFER",
-            ]
-        } else {
-            vec![]
+    /// Applies `f` to `windows`, spread over the configured number of threads.
+    ///
+    /// Generators are pure functions of the pixel position, so the result is
+    /// identical whatever the thread count.
+    fn map_windows<R, F>(&self, windows: &[Window], f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(&Window) -> R + Sync,
+    {
+        if self.threads <= 1 || windows.len() <= 1 {
+            return windows.iter().map(f).collect();
+        }
+
+        let f = &f;
+        let chunk_size = windows.len().div_ceil(self.threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = windows
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<_>>()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("data generator panicked"))
+                .collect()
+        })
+    }
+
+    /// Generates every block once to find the range of the finite values,
+    /// other than nodata, for [`ConversionPolicy::Rescale`].
+    fn observed_range(&self, raster: &RasterInfo, windows: &[Window]) -> (f64, f64) {
+        let ranges = self.map_windows(windows, |window| {
+            let nodata = self.nodata[window.band];
+            let (real, imaginary) = self.generate_values(raster, window);
+            real.into_iter()
+                .chain(imaginary.into_iter().flatten())
+                .filter(|&v| v.is_finite() && Some(v) != nodata)
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+                    (min.min(v), max.max(v))
+                })
+        });
+        ranges.into_iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), (lo, hi)| (min.min(lo), max.max(hi)),
+        )
+    }
+
+    /// Generates the real values of `window`, and the imaginary ones if an
+    /// imaginary generator is set.
+    fn generate_values(
+        &self,
+        raster: &RasterInfo,
+        window: &Window,
+    ) -> (Vec<f64>, Option<Vec<f64>>) {
+        let mut real = vec![0.0; window.len()];
+        self.data_generator
+            .generate_block(raster, window, &mut real);
+
+        let imaginary = self.imaginary_generator.as_ref().map(|generator| {
+            let mut imaginary = vec![0.0; window.len()];
+            generator.generate_block(raster, window, &mut imaginary);
+            imaginary
+        });
+        (real, imaginary)
+    }
+
+    /// Generates the converted values of `window`, and counts them with
+    /// `counter` if given.
+    fn generate_window(
+        &self,
+        raster: &RasterInfo,
+        window: &Window,
+        range: (f64, f64),
+        counter: Option<&PixelCounter>,
+    ) -> Result<(Vec<T>, Option<Vec<u64>>)> {
+        let (real, imaginary) = self.generate_values(raster, window);
+
+        // Nodata values are representable, and left alone by every policy
+        let nodata = self.nodata[window.band];
+        let prepare = |v: f64, index: usize| -> Result<f64> {
+            if Some(v) == nodata {
+                return Ok(v);
+            }
+            self.conversion.prepare::<T>(v, range).ok_or_else(|| {
+                GeoTiffError::ValueOutOfRange(format!(
+                    "{} at pixel ({}, {}) of band {} cannot be represented by {}",
+                    v,
+                    window.x_off + index % window.width,
+                    window.y_off + index / window.width,
+                    window.band,
+                    std::any::type_name::<T>()
+                ))
+            })
         };
-        let options = RasterCreationOptions::from_iter(creation_options);
+
+        // Pixel counts are rejected for complex data in build, so only real
+        // values are ever counted
+        match imaginary {
+            Some(imaginary) => {
+                let data = real
+                    .into_iter()
+                    .zip(imaginary)
+                    .enumerate()
+                    .map(|(index, (re, im))| {
+                        Ok(T::convert_from_parts(
+                            prepare(re, index)?,
+                            prepare(im, index)?,
+                        ))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((data, None))
+            }
+            None => {
+                let values = real
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, v)| prepare(v, index))
+                    .collect::<Result<Vec<f64>>>()?;
+                // Nodata pixels belong to no row
+                let counts = counter.map(|counter| {
+                    let mut counts = vec![0; counter.rows()];
+                    let stored = values
+                        .iter()
+                        .map(|&v| T::stored_value(v))
+                        .filter(|&v| Some(v) != nodata);
+                    counter.count(stored, &mut counts);
+                    counts
+                });
+                let data = values.into_iter().map(T::convert_from_f64).collect();
+                Ok((data, counts))
+            }
+        }
+    }
+
+    /// GTiff creation options for a plain GeoTIFF.
+    fn creation_options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(layout) = self.layout {
+            options.extend(layout.creation_options());
+        }
+        if let Some(interleave) = self.interleave {
+            options.push(interleave.creation_option().to_string());
+        }
+
+        if let Some(compression) = self.compression {
+            options.push(format!("COMPRESS={}", compression.name()));
+            if let (Some(level), Some((option, _, _))) =
+                (self.compression_level, compression.level_range())
+            {
+                options.push(format!("{}={}", option, level));
+            }
+        }
+        if let Some(predictor) = self.predictor {
+            options.push(predictor.creation_option().to_string());
+        }
+
+        if let Some(bigtiff) = self.bigtiff {
+            options.push(bigtiff.creation_option().to_string());
+        }
+
+        options
+    }
+
+    /// GTiff creation options for the temporary file a COG is copied from.
+    ///
+    /// It's only read back once, so it favours speed over size.
+    fn staging_options(&self) -> Vec<String> {
+        let mut options = match self.layout {
+            Some(layout) => layout.creation_options(),
+            None => vec!["TILED=YES".to_string()],
+        };
+        if let Some(interleave) = self.interleave {
+            options.push(interleave.creation_option().to_string());
+        }
+        options.extend([
+            "COMPRESS=DEFLATE".to_string(),
+            "ZLEVEL=1".to_string(),
+            "BIGTIFF=IF_SAFER".to_string(),
+        ]);
+        options
+    }
+
+    /// COG driver creation options, which differ in name from the GTiff ones.
+    fn cog_options(&self) -> Vec<String> {
+        let compression = self.compression.unwrap_or(Compression::Lzw);
+        let mut options = vec![
+            format!("COMPRESS={}", compression.name()),
+            "OVERVIEWS=FORCE_USE_EXISTING".to_string(),
+        ];
+        if let Some(level) = self.compression_level {
+            let option = match compression {
+                Compression::Jpeg | Compression::Webp => "QUALITY",
+                _ => "LEVEL",
+            };
+            options.push(format!("{}={}", option, level));
+        }
+        if let Some(predictor) = self.predictor {
+            options.push(predictor.cog_option().to_string());
+        }
+        if let Some(Layout::Tiles { width, .. }) = self.layout {
+            options.push(format!("BLOCKSIZE={}", width));
+        }
+        options.push(
+            self.bigtiff
+                .unwrap_or(BigTiff::IfSafer)
+                .creation_option()
+                .to_string(),
+        );
+        options
+    }
+
+    /// Creates a GTiff dataset at `path` with the georeferencing and nodata
+    /// values set, ready for [`write_data`](Self::write_data).
+    fn create_dataset(&self, path: &Path, options: Vec<String>) -> Result<Dataset> {
+        let options = RasterCreationOptions::from_iter(options);
 
         let driver = DriverManager::get_driver_by_name("GTiff")?;
         let mut dataset = driver.create_with_band_type_with_options::<T, _>(
-            &self.output_path,
+            path,
             self.width,
             self.height,
             self.bands,
@@ -286,24 +1346,104 @@ where
             dataset.set_geo_transform(&Into::<[f64; 6]>::into(transform.clone()))?;
         }
 
-        let data = self.generate_data();
-
-        for band_index in 1..=self.bands {
-            let mut band = dataset.rasterband(band_index)?;
-            let start = (band_index - 1) * self.width * self.height;
-            let end = band_index * self.width * self.height;
-            let band_data = &data[start..end];
+        for (band_index, nodata) in self.nodata.iter().enumerate() {
+            if let Some(nodata) = *nodata {
+                let mut band = dataset.rasterband(band_index + 1)?;
+                // 64-bit integers don't round-trip through the f64 nodata API
+                match T::datatype() {
+                    GdalDataType::UInt64 => band.set_no_data_value_u64(Some(nodata as u64))?,
+                    GdalDataType::Int64 => band.set_no_data_value_i64(Some(nodata as i64))?,
+                    _ => band.set_no_data_value(Some(nodata))?,
+                }
+            }
+        }
 
-            let mut buffer = Buffer::new((self.width, self.height), band_data.to_vec());
+        if let Some(color_table) = &self.color_table {
+            let mut band = dataset.rasterband(1)?;
+            band.set_color_table(&color_table.to_gdal());
+            band.set_color_interpretation(ColorInterpretation::PaletteIndex)?;
+        }
 
-            band.write((0, 0), (self.width, self.height), &mut buffer)?;
+        for (&band_index, names) in &self.category_names {
+            set_category_names(&mut dataset.rasterband(band_index + 1)?, names)?;
         }
 
+        Ok(dataset)
+    }
+
+    pub fn write(&self) -> Result<()> {
         if self.cloud_optimized {
-            // TIL: empty can be passed as &[] - and here I was going through std::io::empty spiral
-            dataset.build_overviews("NEAREST", &[2, 4, 8, 16], &[])?;
+            return self.write_cog();
+        }
+
+        let mut dataset = self.create_dataset(&self.output_path, self.creation_options())?;
+        self.write_data(&dataset)?;
+
+        match self.overview_storage {
+            OverviewStorage::Internal => self.build_overviews(&mut dataset),
+            OverviewStorage::External => {
+                // Overviews of a dataset opened read-only go to a .ovr file
+                drop(dataset);
+                self.build_overviews(&mut Dataset::open(&self.output_path)?)
+            }
         }
+    }
+
+    fn build_overviews(&self, dataset: &mut Dataset) -> Result<()> {
+        if self.overviews.is_empty() {
+            return Ok(());
+        }
+        let factors: Vec<i32> = self.overviews.iter().map(|&f| f as i32).collect();
+        dataset.build_overviews(self.resampling.name(), &factors, &[])?;
+        Ok(())
+    }
 
+    /// Writes a COG: the data and overviews go to a temporary GeoTIFF next to
+    /// the output, which the COG driver then copies into the final layout
+    /// with the IFDs first and the smallest overview's data before the rest.
+    fn write_cog(&self) -> Result<()> {
+        let mut file_name = std::ffi::OsString::from(".");
+        file_name.push(self.output_path.file_name().unwrap_or_default());
+        file_name.push(".tmp.tif");
+        let staging_path = self.output_path.with_file_name(file_name);
+
+        let result = self.write_staged_cog(&staging_path);
+        // The staging file is removed whether or not the copy succeeded, along
+        // with the .aux.xml GDAL writes for category names and attribute tables
+        let _ = std::fs::remove_file(&staging_path);
+        let mut aux_path = staging_path.into_os_string();
+        aux_path.push(".aux.xml");
+        let _ = std::fs::remove_file(aux_path);
+        result?;
+
+        validate_cog(&self.output_path)
+    }
+
+    fn write_staged_cog(&self, staging_path: &Path) -> Result<()> {
+        let mut dataset = self.create_dataset(staging_path, self.staging_options())?;
+        self.write_data(&dataset)?;
+        self.build_overviews(&mut dataset)?;
+
+        let driver = DriverManager::get_driver_by_name("COG")?;
+        dataset.create_copy(
+            &driver,
+            &self.output_path,
+            &RasterCreationOptions::from_iter(self.cog_options()),
+        )?;
         Ok(())
     }
 }
+
+fn set_category_names(band: &mut RasterBand, names: &BTreeMap<usize, String>) -> Result<()> {
+    // GDAL expects a list indexed by pixel value
+    let mut list = CslStringList::new();
+    let len = names.keys().next_back().map_or(0, |&value| value + 1);
+    for value in 0..len {
+        list.add_string(names.get(&value).map_or("", String::as_str))?;
+    }
+    let error = unsafe { gdal_sys::GDALSetRasterCategoryNames(band.c_rasterband(), list.as_ptr()) };
+    if error != gdal_sys::CPLErr::CE_None {
+        return Err(last_gdal_error(error));
+    }
+    Ok(())
+}
@@ -1,8 +1,25 @@
+pub mod cog;
+pub mod colors;
 pub mod conversions;
 pub mod error;
+pub mod expression;
 pub mod geotiff;
+pub mod options;
 pub mod patterns;
+pub mod rat;
 
+pub use cog::validate_cog;
 pub use error::{GeoTiffError, Result};
+pub use expression::ExpressionPattern;
 pub use geotiff::{FakeGeoTiff, FakeGeoTiffBuilder, GeoTransform};
-pub use patterns::{DataGenerator, GradientPattern, NoisePattern, SineWavePattern};
+pub use options::{
+    BigTiff, Compression, Interleave, Layout, OverviewStorage, Overviews, Predictor, Resampling,
+};
+pub use patterns::{
+    Abs, Affine, CategoricalPattern, CheckerboardPattern, Clamp, CoherentNoise, ConstantPattern,
+    DataGenerator, ExponentialNoise, Fbm, GaussianNoise, GradientPattern, GridPattern, HoleMask,
+    MapRange, Mix, NoDataHoles, NoisePattern, PatternExt, PerBand, PerlinNoise, PixelContext,
+    PoissonNoise, Product, RadialGradientPattern, RasterInfo, RingPattern, SelectBand,
+    SiemensStarPattern, SimplexNoise, SineWavePattern, StripePattern, Sum, TerrainPattern,
+    Threshold, UniformNoise, Window,
+};
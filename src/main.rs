@@ -1,8 +1,18 @@
-use clap::Parser;
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches, Parser, ValueEnum};
 use rasterfakers::{
-    patterns::{GradientPattern, NoisePattern, SineWavePattern},
-    DataGenerator, FakeGeoTiffBuilder, GeoTransform,
+    colors::{Color, ColorRamp, ColorTable},
+    conversions::{Complex, ConversionPolicy},
+    patterns::{
+        CategoricalPattern, CheckerboardPattern, ConstantPattern, ExponentialNoise, Fbm,
+        GaussianNoise, GradientPattern, GridPattern, MapRange, NoisePattern, PerlinNoise,
+        PoissonNoise, RadialGradientPattern, RingPattern, SiemensStarPattern, SimplexNoise,
+        SineWavePattern, StripePattern, TerrainPattern, UniformNoise,
+    },
+    rat::{FieldType, FieldUsage, RasterAttributeTable, RatValue},
+    BigTiff, Compression, DataGenerator, ExpressionPattern, FakeGeoTiffBuilder, GeoTiffError,
+    GeoTransform, HoleMask, Interleave, Layout, OverviewStorage, Overviews, Predictor, Resampling,
 };
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -26,7 +36,8 @@ struct CliArgs {
     #[arg(short = 'b', long, default_value_t = 1)]
     bands: usize,
 
-    /// Data type (u8, u16, i16, u32, i32, f32, f64)
+    /// Data type (u8, i8, u16, i16, u32, i32, u64, i64, f32, f64, or complex ci16, ci32, cf32,
+    /// cf64)
     #[arg(short = 't', long, default_value = "f64")]
     data_type: String,
 
@@ -42,17 +53,162 @@ struct CliArgs {
     #[arg(short = 'c', long, default_value = "0.0,0.0")]
     upper_left_corner: String,
 
-    /// Data pattern (gradient, sine, noise)
+    /// Data pattern, optionally with parameters as NAME:KEY=VALUE:KEY=VALUE, or a
+    /// comma-separated list with one pattern per band (e.g., gradient,noise,sine)
+    ///
+    /// Patterns, with their parameters and defaults: gradient, sine, noise, constant:value=0,
+    /// uniform:min=0:max=255, gaussian:mean=127.5:std_dev=32, poisson:lambda=10,
+    /// exponential:rate=0.05, perlin:frequency=0.02, simplex:frequency=0.02,
+    /// fbm:basis=perlin:frequency=0.01:octaves=4:lacunarity=2:persistence=0.5,
+    /// terrain:min=0:max=2500:roughness=0.5:size=256, checkerboard:size=8,
+    /// stripes:width=8:direction=horizontal, rings:width=16, radial,
+    /// grid:spacing=10:line_width=1 (spacing in map units), siemens:spokes=16,
+    /// categorical:classes=1/2/3:proportions=5/3/2:size=64.
+    ///
+    /// expr:EXPRESSION computes each pixel from x, y, band, map_x, map_y, width, height and
+    /// bands, e.g. 'expr:sin(x/10)*100+band'.
     #[arg(short = 'n', long, default_value = "gradient")]
     pattern: String,
 
+    /// Map the pattern's natural range onto [MIN, MAX]; requires --max
+    #[arg(long, allow_negative_numbers = true, requires = "max")]
+    min: Option<f64>,
+
+    /// Map the pattern's natural range onto [MIN, MAX]; requires --min
+    #[arg(long, allow_negative_numbers = true, requires = "min")]
+    max: Option<f64>,
+
+    /// Pattern of the imaginary part of complex data types, seeded with --seed + 1
+    #[arg(long)]
+    imaginary_pattern: Option<String>,
+
+    /// How values that don't fit the data type are converted (saturate, round, wrap, error,
+    /// rescale)
+    #[arg(long, default_value = "saturate")]
+    conversion: ConversionPolicy,
+
     /// COG flag
     #[arg(long, default_value_t = false)]
     cloud_optimized: bool,
+
+    /// NoData value written to every band (e.g., -9999)
+    #[arg(long, allow_negative_numbers = true)]
+    nodata: Option<f64>,
+
+    /// NoData holes to inject (scattered:FRACTION, blobs:FRACTION:SIZE, rows:FRACTION,
+    /// tiles:FRACTION:SIZE, rect:X,Y,WIDTH,HEIGHT); requires --nodata
+    #[arg(long)]
+    nodata_holes: Option<String>,
+
+    /// Color table of a single band u8 or u16 raster, as VALUE=#RRGGBB pairs
+    /// (e.g., "1=#1a9850,2=#4575b4"), a ramp over values 0..N (viridis, magma, inferno,
+    /// plasma or grayscale, e.g. "viridis:256"), or N distinct colours seeded with --seed
+    /// ("random:N"); N defaults to 256
+    #[arg(long)]
+    color_table: Option<String>,
+
+    /// Category names of every band, as VALUE=NAME pairs (e.g., "1=Forest,2=Water")
+    #[arg(long)]
+    category_names: Option<String>,
+
+    /// Attach a raster attribute table to every band, with a row per value of --category-names
+    /// and --color-table, and the pixel count of each
+    #[arg(long, default_value_t = false)]
+    attribute_table: bool,
+
+    /// Seed for random patterns and nodata holes
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Write BigTIFF, needed for files over 4 GB (yes, no, if-needed, if-safer)
+    #[arg(long)]
+    bigtiff: Option<BigTiff>,
+
+    /// Compression codec (none, lzw, deflate, zstd, lzma, jpeg, webp, lerc, packbits)
+    #[arg(long)]
+    compress: Option<Compression>,
+
+    /// Compression level, or quality for jpeg and webp
+    #[arg(long)]
+    level: Option<u32>,
+
+    /// Predictor applied before compression (none, horizontal, floating-point)
+    #[arg(long)]
+    predictor: Option<Predictor>,
+
+    /// Tile size, as N or WIDTHxHEIGHT in multiples of 16, square for COGs (e.g., 512 or 256x128)
+    #[arg(long, conflicts_with = "rows_per_strip")]
+    tile_size: Option<String>,
+
+    /// Write strips of N rows each instead of tiles
+    #[arg(long)]
+    rows_per_strip: Option<usize>,
+
+    /// Band interleaving of multiband output (pixel, band); COGs are always pixel interleaved
+    #[arg(long)]
+    interleave: Option<Interleave>,
+
+    /// Overview levels (none, auto, auto:MIN_SIZE, or factors like 2,4,8) [default: auto for
+    /// COGs, none otherwise]
+    #[arg(long)]
+    overviews: Option<Overviews>,
+
+    /// Overview resampling (nearest, average, bilinear, cubic, mode, gauss)
+    #[arg(long)]
+    resampling: Option<Resampling>,
+
+    /// Store overviews in an external .ovr file instead of the GeoTIFF
+    #[arg(long, default_value_t = false)]
+    external_overviews: bool,
+
+    /// Number of threads generating pixel data, at most one per core (0 uses all cores)
+    #[arg(long, default_value_t = 1)]
+    threads: usize,
+
+    /// Preset that changes the defaults of the other options
+    #[arg(long, value_enum)]
+    preset: Option<Preset>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Preset {
+    /// Terrain pattern as f32 in UTM zone 32N at 30 m resolution, with -9999 as nodata
+    Dem,
+}
+
+impl Preset {
+    /// Overrides every option the user didn't pass explicitly.
+    fn apply(self, args: &mut CliArgs, matches: &ArgMatches) {
+        let is_default = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+        match self {
+            Preset::Dem => {
+                if is_default("pattern") {
+                    args.pattern = "terrain".into();
+                }
+                if is_default("data_type") {
+                    args.data_type = "f32".into();
+                }
+                if is_default("projection") {
+                    args.projection = "EPSG:32632".into();
+                }
+                if is_default("pixel_resolution") {
+                    args.pixel_resolution = "30.0,30.0".into();
+                }
+                if is_default("upper_left_corner") {
+                    args.upper_left_corner = "500000.0,5400000.0".into();
+                }
+                args.nodata = args.nodata.or(Some(-9999.0));
+            }
+        }
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = CliArgs::parse();
+    let matches = CliArgs::command().get_matches();
+    let mut args = CliArgs::from_arg_matches(&matches)?;
+    if let Some(preset) = args.preset {
+        preset.apply(&mut args, &matches);
+    }
 
     // Parse pixel resolution and upper-left corner
     let (pixel_width, pixel_height) = parse_tuple(&args.pixel_resolution)?;
@@ -67,29 +223,136 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         pixel_height: -pixel_height,
     };
 
-    let data_generator: Box<dyn DataGenerator> = match args.pattern.as_str() {
-        "sine" => Box::new(SineWavePattern),
-        "noise" => Box::new(NoisePattern),
-        _ => Box::new(GradientPattern),
+    let map_range = |generator: Box<dyn DataGenerator>| -> Box<dyn DataGenerator> {
+        match (args.min, args.max) {
+            (Some(min), Some(max)) => Box::new(MapRange::new(generator, min, max)),
+            _ => generator,
+        }
     };
 
-    let builder = FakeGeoTiffBuilder::new()
+    let patterns = split_list(&args.pattern);
+    if patterns.len() != 1 && patterns.len() != args.bands {
+        return Err(format!(
+            "Expected 1 pattern or one per band ({}), got {}",
+            args.bands,
+            patterns.len()
+        )
+        .into());
+    }
+    let data_generator = map_range(parse_pattern(patterns[0], args.seed)?);
+
+    let mut builder = FakeGeoTiffBuilder::new()
         .dimensions(args.width, args.height)?
         .bands(args.bands)?
         .projection(args.projection)
         .geotransform(geotransform)
         .output_path(args.output.clone())
         .data_generator(data_generator)
-        .cloud_optimized(args.cloud_optimized);
+        .conversion_policy(args.conversion)
+        .cloud_optimized(args.cloud_optimized)
+        .threads(args.threads);
+
+    for (band, pattern) in patterns.iter().enumerate().skip(1) {
+        builder = builder.band_generator(band, map_range(parse_pattern(pattern, args.seed)?));
+    }
+
+    if let Some(nodata) = args.nodata {
+        builder = builder.nodata(nodata);
+    }
+
+    if let Some(bigtiff) = args.bigtiff {
+        builder = builder.bigtiff(bigtiff);
+    }
+
+    if let Some(compression) = args.compress {
+        builder = builder.compression(compression);
+    }
+
+    if let Some(level) = args.level {
+        builder = builder.compression_level(level);
+    }
+
+    if let Some(predictor) = args.predictor {
+        builder = builder.predictor(predictor);
+    }
+
+    if let Some(tile_size) = &args.tile_size {
+        let (width, height) = parse_tile_size(tile_size)?;
+        builder = builder.layout(Layout::Tiles { width, height });
+    }
+
+    if let Some(rows_per_strip) = args.rows_per_strip {
+        builder = builder.layout(Layout::Strips { rows_per_strip });
+    }
+
+    if let Some(interleave) = args.interleave {
+        builder = builder.interleave(interleave);
+    }
+
+    if let Some(overviews) = args.overviews.clone() {
+        builder = builder.overviews(overviews);
+    }
+
+    if let Some(resampling) = args.resampling {
+        builder = builder.overview_resampling(resampling);
+    }
+
+    if args.external_overviews {
+        builder = builder.overview_storage(OverviewStorage::External);
+    }
+
+    if let Some(pattern) = &args.imaginary_pattern {
+        let imaginary = parse_pattern(pattern, args.seed.wrapping_add(1))?;
+        builder = builder.imaginary_generator(map_range(imaginary));
+    }
+
+    if let Some(holes) = &args.nodata_holes {
+        builder = builder.nodata_holes(parse_hole_mask(holes)?, args.seed);
+    }
+
+    let color_table = args
+        .color_table
+        .as_deref()
+        .map(|s| parse_color_table(s, args.seed))
+        .transpose()?;
+    let category_names = args
+        .category_names
+        .as_deref()
+        .map(parse_value_pairs)
+        .transpose()?;
+
+    if args.attribute_table {
+        let table = attribute_table(color_table.as_ref(), category_names.as_deref())?;
+        for band in 0..args.bands {
+            builder = builder.attribute_table(band, table.clone());
+        }
+    }
+
+    if let Some(color_table) = color_table {
+        builder = builder.color_table(color_table);
+    }
+
+    if let Some(names) = category_names {
+        for band in 0..args.bands {
+            builder = builder.category_names(band, names.iter().cloned());
+        }
+    }
 
     match args.data_type.as_str() {
         "u8" => builder.build::<u8>()?.write()?,
+        "i8" => builder.build::<i8>()?.write()?,
         "u16" => builder.build::<u16>()?.write()?,
         "i16" => builder.build::<i16>()?.write()?,
         "u32" => builder.build::<u32>()?.write()?,
         "i32" => builder.build::<i32>()?.write()?,
+        "u64" => builder.build::<u64>()?.write()?,
+        "i64" => builder.build::<i64>()?.write()?,
         "f32" => builder.build::<f32>()?.write()?,
         "f64" => builder.build::<f64>()?.write()?,
+        "ci16" => builder.build::<Complex<i16>>()?.write()?,
+        "ci32" => builder.build::<Complex<i32>>()?.write()?,
+        "cf32" => builder.build::<Complex<f32>>()?.write()?,
+        "cf64" => builder.build::<Complex<f64>>()?.write()?,
         _ => return Err(format!("Unsupported data type: {}", args.data_type).into()),
     }
 
@@ -110,3 +373,349 @@ fn parse_tuple(s: &str) -> Result<(f64, f64), Box<dyn std::error::Error>> {
     let second = parts[1].trim().parse::<f64>()?;
     Ok((first, second))
 }
+
+fn parse_tile_size(s: &str) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+    match s.split_once('x') {
+        Some((width, height)) => Ok((width.trim().parse()?, height.trim().parse()?)),
+        None => {
+            let size = s.trim().parse()?;
+            Ok((size, size))
+        }
+    }
+}
+
+/// Splits a comma-separated list, ignoring commas inside parentheses.
+fn split_list(s: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(s[start..].trim());
+    items
+}
+
+/// A pattern name with optional `:key=value` parameters.
+struct PatternSpec<'a> {
+    name: &'a str,
+    params: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> PatternSpec<'a> {
+    fn parse(s: &'a str) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut parts = s.split(':').map(str::trim);
+        let name = parts.next().unwrap_or_default();
+        let mut params = HashMap::new();
+        for part in parts {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Expected KEY=VALUE in pattern '{}', got '{}'", s, part))?;
+            params.insert(key.trim(), value.trim());
+        }
+        Ok(Self { name, params })
+    }
+
+    /// Fails if any parameter is not in `allowed`.
+    fn expect_keys(&self, allowed: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+        match self.params.keys().find(|key| !allowed.contains(key)) {
+            Some(key) => Err(format!(
+                "Unknown parameter '{}' for pattern '{}' (expected one of: {})",
+                key,
+                self.name,
+                allowed.join(", ")
+            )
+            .into()),
+            None => Ok(()),
+        }
+    }
+
+    fn get<T>(&self, key: &str, default: T) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T: std::str::FromStr,
+        T::Err: std::error::Error + 'static,
+    {
+        match self.params.get(key) {
+            Some(value) => Ok(value.parse()?),
+            None => Ok(default),
+        }
+    }
+}
+
+fn parse_pattern(s: &str, seed: u64) -> Result<Box<dyn DataGenerator>, Box<dyn std::error::Error>> {
+    // Expressions contain ':' and '=', so they bypass the KEY=VALUE syntax
+    if let Some(source) = s.strip_prefix("expr:") {
+        return Ok(Box::new(ExpressionPattern::parse(source)?));
+    }
+
+    let spec = PatternSpec::parse(s)?;
+    let generator: Box<dyn DataGenerator> = match spec.name {
+        "gradient" => Box::new(GradientPattern),
+        "sine" => Box::new(SineWavePattern),
+        "noise" => Box::new(NoisePattern),
+        "constant" => {
+            spec.expect_keys(&["value"])?;
+            Box::new(ConstantPattern(spec.get("value", 0.0)?))
+        }
+        "uniform" => {
+            spec.expect_keys(&["min", "max"])?;
+            Box::new(UniformNoise::new(spec.get("min", 0.0)?, spec.get("max", 255.0)?).seed(seed))
+        }
+        "gaussian" | "normal" => {
+            spec.expect_keys(&["mean", "std_dev"])?;
+            Box::new(
+                GaussianNoise::new(spec.get("mean", 127.5)?, spec.get("std_dev", 32.0)?).seed(seed),
+            )
+        }
+        "poisson" => {
+            spec.expect_keys(&["lambda"])?;
+            Box::new(PoissonNoise::new(spec.get("lambda", 10.0)?).seed(seed))
+        }
+        "exponential" => {
+            spec.expect_keys(&["rate"])?;
+            Box::new(ExponentialNoise::new(spec.get("rate", 0.05)?).seed(seed))
+        }
+        "perlin" => {
+            spec.expect_keys(&["frequency"])?;
+            Box::new(PerlinNoise::new(spec.get("frequency", 0.02)?).seed(seed))
+        }
+        "simplex" => {
+            spec.expect_keys(&["frequency"])?;
+            Box::new(SimplexNoise::new(spec.get("frequency", 0.02)?).seed(seed))
+        }
+        "fbm" => {
+            spec.expect_keys(&["basis", "frequency", "octaves", "lacunarity", "persistence"])?;
+            let frequency = spec.get("frequency", 0.01)?;
+            let octaves = spec.get("octaves", 4)?;
+            let lacunarity = spec.get("lacunarity", 2.0)?;
+            let persistence = spec.get("persistence", 0.5)?;
+            match spec.get("basis", String::from("perlin"))?.as_str() {
+                "perlin" => Box::new(
+                    Fbm::new(PerlinNoise::new(1.0).seed(seed))
+                        .frequency(frequency)
+                        .octaves(octaves)
+                        .lacunarity(lacunarity)
+                        .persistence(persistence),
+                ),
+                "simplex" => Box::new(
+                    Fbm::new(SimplexNoise::new(1.0).seed(seed))
+                        .frequency(frequency)
+                        .octaves(octaves)
+                        .lacunarity(lacunarity)
+                        .persistence(persistence),
+                ),
+                basis => return Err(format!("Unsupported fbm basis: '{}'", basis).into()),
+            }
+        }
+        "terrain" => {
+            spec.expect_keys(&["min", "max", "roughness", "size"])?;
+            Box::new(
+                TerrainPattern::new(spec.get("min", 0.0)?, spec.get("max", 2500.0)?)
+                    .roughness(spec.get("roughness", 0.5)?)
+                    .feature_size(spec.get("size", 256.0)?)
+                    .seed(seed),
+            )
+        }
+        "checkerboard" => {
+            spec.expect_keys(&["size"])?;
+            Box::new(CheckerboardPattern::new(spec.get("size", 8)?))
+        }
+        "stripes" => {
+            spec.expect_keys(&["width", "direction"])?;
+            let width = spec.get("width", 8)?;
+            match spec.get("direction", String::from("horizontal"))?.as_str() {
+                "horizontal" => Box::new(StripePattern::horizontal(width)),
+                "vertical" => Box::new(StripePattern::vertical(width)),
+                direction => {
+                    return Err(format!("Unsupported stripe direction: '{}'", direction).into())
+                }
+            }
+        }
+        "rings" => {
+            spec.expect_keys(&["width"])?;
+            Box::new(RingPattern::new(spec.get("width", 16)?))
+        }
+        "radial" => Box::new(RadialGradientPattern),
+        "grid" => {
+            spec.expect_keys(&["spacing", "line_width"])?;
+            Box::new(
+                GridPattern::new(spec.get("spacing", 10.0)?).line_width(spec.get("line_width", 1)?),
+            )
+        }
+        "categorical" => {
+            spec.expect_keys(&["classes", "proportions", "size"])?;
+            let classes: Vec<f64> = spec
+                .get("classes", String::from("1/2/3/4/5"))?
+                .split('/')
+                .map(|code| code.trim().parse())
+                .collect::<Result<_, _>>()?;
+            let proportions: Vec<f64> = match spec.params.get("proportions") {
+                Some(proportions) => proportions
+                    .split('/')
+                    .map(|p| p.trim().parse())
+                    .collect::<Result<_, _>>()?,
+                None => vec![1.0; classes.len()],
+            };
+            if proportions.len() != classes.len() {
+                return Err(format!(
+                    "Expected one proportion per class ({}), got {}",
+                    classes.len(),
+                    proportions.len()
+                )
+                .into());
+            }
+            Box::new(
+                CategoricalPattern::new(classes.into_iter().zip(proportions))?
+                    .cell_size(spec.get("size", 64.0)?)
+                    .seed(seed),
+            )
+        }
+        "siemens" => {
+            spec.expect_keys(&["spokes"])?;
+            Box::new(SiemensStarPattern::new(spec.get("spokes", 16)?))
+        }
+        name => {
+            return Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown pattern '{}' (expected one of: gradient, sine, noise, constant, \
+                 uniform, gaussian, poisson, exponential, perlin, simplex, fbm, terrain, \
+                 checkerboard, stripes, rings, radial, grid, siemens, categorical, expr)",
+                name
+            ))
+            .into())
+        }
+    };
+    Ok(generator)
+}
+
+/// Parses comma-separated `VALUE=TEXT` pairs, as used by `--color-table`
+/// and `--category-names`.
+fn parse_value_pairs(s: &str) -> Result<Vec<(usize, String)>, Box<dyn std::error::Error>> {
+    s.split(',')
+        .map(|pair| {
+            let (value, text) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("Expected VALUE=TEXT, got '{}'", pair))?;
+            Ok((value.trim().parse()?, text.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Parses `--color-table`: VALUE=#RRGGBB pairs, or a generated palette
+/// such as `viridis:256` or `random:10`.
+fn parse_color_table(s: &str, seed: u64) -> Result<ColorTable, Box<dyn std::error::Error>> {
+    if !s.contains('=') {
+        let (name, count) = match s.split_once(':') {
+            Some((name, count)) => (name, count.trim().parse()?),
+            None => (s, 256),
+        };
+        return Ok(match name.trim() {
+            "random" => ColorTable::distinct(count, seed),
+            ramp => ColorTable::ramp(ramp.parse::<ColorRamp>()?, count),
+        });
+    }
+
+    parse_value_pairs(s)?
+        .into_iter()
+        .map(|(value, color)| Ok((u16::try_from(value)?, color.parse::<Color>()?)))
+        .collect()
+}
+
+/// Builds a thematic attribute table with a row per value that has a
+/// category name or a colour, and the pixel count of each.
+fn attribute_table(
+    color_table: Option<&ColorTable>,
+    category_names: Option<&[(usize, String)]>,
+) -> Result<RasterAttributeTable, Box<dyn std::error::Error>> {
+    let names: BTreeMap<usize, &str> = category_names
+        .unwrap_or_default()
+        .iter()
+        .map(|(value, name)| (*value, name.as_str()))
+        .collect();
+    let mut values: BTreeSet<usize> = names.keys().copied().collect();
+    if let Some(color_table) = color_table {
+        values.extend(color_table.iter().map(|(value, _)| usize::from(value)));
+    }
+    if values.is_empty() {
+        return Err("--attribute-table requires --category-names or --color-table".into());
+    }
+
+    let mut table =
+        RasterAttributeTable::new().column("Value", FieldType::Integer, FieldUsage::MinMax);
+    if category_names.is_some() {
+        table = table.column("Name", FieldType::String, FieldUsage::Name);
+    }
+    if color_table.is_some() {
+        for (name, usage) in [
+            ("Red", FieldUsage::Red),
+            ("Green", FieldUsage::Green),
+            ("Blue", FieldUsage::Blue),
+            ("Alpha", FieldUsage::Alpha),
+        ] {
+            table = table.column(name, FieldType::Integer, usage);
+        }
+    }
+
+    for value in values {
+        let mut row = vec![RatValue::Integer(i32::try_from(value)?)];
+        if category_names.is_some() {
+            row.push(names.get(&value).copied().unwrap_or_default().into());
+        }
+        if let Some(color_table) = color_table {
+            let color = u16::try_from(value)
+                .ok()
+                .and_then(|value| color_table.get(value))
+                .unwrap_or(Color::rgba(0, 0, 0, 0));
+            row.extend([color.r, color.g, color.b, color.a].map(|c| RatValue::Integer(c.into())));
+        }
+        table = table.row(row);
+    }
+    Ok(table.pixel_counts("Count"))
+}
+
+fn parse_hole_mask(s: &str) -> Result<HoleMask, Box<dyn std::error::Error>> {
+    let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+    let mask = match parts.as_slice() {
+        ["scattered", fraction] => HoleMask::Scattered {
+            fraction: fraction.parse()?,
+        },
+        ["blobs", fraction, size] => HoleMask::Blobs {
+            fraction: fraction.parse()?,
+            size: size.parse()?,
+        },
+        ["rows", fraction] => HoleMask::Rows {
+            fraction: fraction.parse()?,
+        },
+        ["tiles", fraction, size] => HoleMask::Tiles {
+            fraction: fraction.parse()?,
+            size: size.parse()?,
+        },
+        ["rect", rect] => {
+            let values = rect
+                .split(',')
+                .map(|v| v.trim().parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()?;
+            match values.as_slice() {
+                &[x, y, width, height] => HoleMask::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+                _ => {
+                    return Err(
+                        format!("Expected four comma-separated values, got '{}'", rect).into(),
+                    )
+                }
+            }
+        }
+        _ => return Err(format!("Unsupported nodata hole specification: '{}'", s).into()),
+    };
+    Ok(mask)
+}
@@ -0,0 +1,387 @@
+use crate::error::{GeoTiffError, Result};
+use std::str::FromStr;
+
+/// Whether the output is written as BigTIFF, which lifts the 4 GB limit of classic TIFF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BigTiff {
+    /// Always write BigTIFF.
+    Yes,
+    /// Always write classic TIFF.
+    No,
+    /// Write BigTIFF when the uncompressed data won't fit in classic TIFF.
+    IfNeeded,
+    /// Write BigTIFF when the file might not fit in classic TIFF, even after compression.
+    IfSafer,
+}
+
+impl BigTiff {
+    pub(crate) fn creation_option(self) -> &'static str {
+        match self {
+            BigTiff::Yes => "BIGTIFF=YES",
+            BigTiff::No => "BIGTIFF=NO",
+            BigTiff::IfNeeded => "BIGTIFF=IF_NEEDED",
+            BigTiff::IfSafer => "BIGTIFF=IF_SAFER",
+        }
+    }
+}
+
+impl FromStr for BigTiff {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "yes" => Ok(BigTiff::Yes),
+            "no" => Ok(BigTiff::No),
+            "if-needed" => Ok(BigTiff::IfNeeded),
+            "if-safer" => Ok(BigTiff::IfSafer),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown BigTIFF mode '{}' (expected yes, no, if-needed or if-safer)",
+                s
+            ))),
+        }
+    }
+}
+
+/// The codec used to compress the GeoTIFF's blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Lzw,
+    Deflate,
+    Zstd,
+    Lzma,
+    /// Lossy, `u8` data only.
+    Jpeg,
+    /// Lossy or lossless, `u8` data only.
+    Webp,
+    Lerc,
+    Packbits,
+}
+
+impl Compression {
+    /// The codec's name in GDAL's `COMPRESS` creation option.
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::None => "NONE",
+            Compression::Lzw => "LZW",
+            Compression::Deflate => "DEFLATE",
+            Compression::Zstd => "ZSTD",
+            Compression::Lzma => "LZMA",
+            Compression::Jpeg => "JPEG",
+            Compression::Webp => "WEBP",
+            Compression::Lerc => "LERC",
+            Compression::Packbits => "PACKBITS",
+        }
+    }
+
+    /// The creation option controlling the codec's level or quality, and its valid range.
+    pub fn level_range(self) -> Option<(&'static str, u32, u32)> {
+        match self {
+            Compression::Deflate => Some(("ZLEVEL", 1, 12)),
+            Compression::Zstd => Some(("ZSTD_LEVEL", 1, 22)),
+            Compression::Lzma => Some(("LZMA_PRESET", 0, 9)),
+            Compression::Jpeg => Some(("JPEG_QUALITY", 1, 100)),
+            Compression::Webp => Some(("WEBP_LEVEL", 1, 100)),
+            _ => None,
+        }
+    }
+
+    /// Whether the codec can be combined with a [`Predictor`].
+    pub fn supports_predictor(self) -> bool {
+        matches!(
+            self,
+            Compression::Lzw | Compression::Deflate | Compression::Zstd | Compression::Lzma
+        )
+    }
+}
+
+impl FromStr for Compression {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Compression::None),
+            "lzw" => Ok(Compression::Lzw),
+            "deflate" => Ok(Compression::Deflate),
+            "zstd" => Ok(Compression::Zstd),
+            "lzma" => Ok(Compression::Lzma),
+            "jpeg" => Ok(Compression::Jpeg),
+            "webp" => Ok(Compression::Webp),
+            "lerc" => Ok(Compression::Lerc),
+            "packbits" => Ok(Compression::Packbits),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown compression '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// The predictor applied before compression, which helps codecs with smooth data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predictor {
+    None,
+    /// Horizontal differencing, for any data type.
+    Horizontal,
+    /// Floating point prediction, for `f32` and `f64` data only.
+    FloatingPoint,
+}
+
+impl Predictor {
+    pub(crate) fn creation_option(self) -> &'static str {
+        match self {
+            Predictor::None => "PREDICTOR=1",
+            Predictor::Horizontal => "PREDICTOR=2",
+            Predictor::FloatingPoint => "PREDICTOR=3",
+        }
+    }
+
+    pub(crate) fn cog_option(self) -> &'static str {
+        match self {
+            Predictor::None => "PREDICTOR=NO",
+            Predictor::Horizontal => "PREDICTOR=STANDARD",
+            Predictor::FloatingPoint => "PREDICTOR=FLOATING_POINT",
+        }
+    }
+}
+
+impl FromStr for Predictor {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "none" | "1" => Ok(Predictor::None),
+            "horizontal" | "2" => Ok(Predictor::Horizontal),
+            "floating-point" | "3" => Ok(Predictor::FloatingPoint),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown predictor '{}' (expected none, horizontal or floating-point)",
+                s
+            ))),
+        }
+    }
+}
+
+/// The internal layout of the GeoTIFF's blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Full-width strips of `rows_per_strip` rows each.
+    Strips { rows_per_strip: usize },
+    /// Tiles of `width` x `height` pixels, both multiples of 16.
+    Tiles { width: usize, height: usize },
+}
+
+impl Layout {
+    pub(crate) fn creation_options(self) -> Vec<String> {
+        match self {
+            Layout::Strips { rows_per_strip } => {
+                vec![
+                    "TILED=NO".to_string(),
+                    format!("BLOCKYSIZE={}", rows_per_strip),
+                ]
+            }
+            Layout::Tiles { width, height } => vec![
+                "TILED=YES".to_string(),
+                format!("BLOCKXSIZE={}", width),
+                format!("BLOCKYSIZE={}", height),
+            ],
+        }
+    }
+
+    pub(crate) fn validate(self) -> Result<()> {
+        match self {
+            Layout::Strips { rows_per_strip: 0 } => Err(GeoTiffError::InvalidBlockSize(
+                "Rows per strip must be greater than 0".into(),
+            )),
+            Layout::Tiles { width, height }
+                if width == 0 || height == 0 || width % 16 != 0 || height % 16 != 0 =>
+            {
+                Err(GeoTiffError::InvalidBlockSize(format!(
+                    "Tile size must be a non-zero multiple of 16, got {}x{}",
+                    width, height
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Which overview levels are built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Overviews {
+    /// No overviews.
+    None,
+    /// Overviews at the given reduction factors, e.g. `[2, 4, 8]`.
+    Factors(Vec<usize>),
+    /// Overviews at factors of 2, 4, 8, ... until the largest dimension is
+    /// no more than `min_size` pixels.
+    Auto { min_size: usize },
+}
+
+impl Overviews {
+    /// The reduction factors for a `width` x `height` raster.
+    pub fn factors(&self, width: usize, height: usize) -> Vec<usize> {
+        match self {
+            Overviews::None => Vec::new(),
+            Overviews::Factors(factors) => factors.clone(),
+            Overviews::Auto { min_size } => {
+                let size = width.max(height);
+                let mut factors = Vec::new();
+                let mut factor = 2;
+                while size.div_ceil(factor / 2) > *min_size {
+                    factors.push(factor);
+                    factor *= 2;
+                }
+                factors
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self, width: usize, height: usize) -> Result<()> {
+        match self {
+            Overviews::None => Ok(()),
+            Overviews::Factors(factors) => {
+                match factors.iter().find(|&&f| f < 2 || f > width.max(height)) {
+                    Some(factor) => Err(GeoTiffError::InvalidParameter(format!(
+                        "Overview factor {} must be between 2 and the raster size of {}x{}",
+                        factor, width, height
+                    ))),
+                    None => Ok(()),
+                }
+            }
+            Overviews::Auto { min_size: 0 } => Err(GeoTiffError::InvalidParameter(
+                "Overview minimum size must be greater than 0".into(),
+            )),
+            Overviews::Auto { .. } => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Overviews {
+    type Err = GeoTiffError;
+
+    /// Parses `none`, `auto`, `auto:MIN_SIZE` or a list of factors like `2,4,8`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            GeoTiffError::InvalidParameter(format!(
+                "Invalid overviews '{}' (expected none, auto, auto:MIN_SIZE or factors like 2,4,8)",
+                s
+            ))
+        };
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Overviews::None),
+            "auto" => Ok(Overviews::Auto { min_size: 256 }),
+            s => match s.strip_prefix("auto:") {
+                Some(min_size) => Ok(Overviews::Auto {
+                    min_size: min_size.trim().parse().map_err(|_| invalid())?,
+                }),
+                None => s
+                    .split(',')
+                    .map(|factor| factor.trim().parse().map_err(|_| invalid()))
+                    .collect::<Result<_>>()
+                    .map(Overviews::Factors),
+            },
+        }
+    }
+}
+
+/// The resampling method used to compute overviews.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resampling {
+    Nearest,
+    Average,
+    Bilinear,
+    Cubic,
+    /// The most common value, for categorical data.
+    Mode,
+    Gauss,
+}
+
+impl Resampling {
+    /// The method's name in GDAL's overview building.
+    pub fn name(self) -> &'static str {
+        match self {
+            Resampling::Nearest => "NEAREST",
+            Resampling::Average => "AVERAGE",
+            Resampling::Bilinear => "BILINEAR",
+            Resampling::Cubic => "CUBIC",
+            Resampling::Mode => "MODE",
+            Resampling::Gauss => "GAUSS",
+        }
+    }
+}
+
+impl FromStr for Resampling {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "nearest" => Ok(Resampling::Nearest),
+            "average" => Ok(Resampling::Average),
+            "bilinear" => Ok(Resampling::Bilinear),
+            "cubic" => Ok(Resampling::Cubic),
+            "mode" => Ok(Resampling::Mode),
+            "gauss" => Ok(Resampling::Gauss),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown resampling '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Where the overviews of a plain GeoTIFF are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverviewStorage {
+    /// Inside the GeoTIFF itself.
+    Internal,
+    /// In a `.ovr` file next to the GeoTIFF.
+    External,
+}
+
+impl FromStr for OverviewStorage {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "internal" => Ok(OverviewStorage::Internal),
+            "external" => Ok(OverviewStorage::External),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown overview storage '{}' (expected internal or external)",
+                s
+            ))),
+        }
+    }
+}
+
+/// How the bands of a multiband GeoTIFF are interleaved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interleave {
+    /// All bands of a pixel are stored together, in each block.
+    Pixel,
+    /// Each band is stored in its own blocks.
+    Band,
+}
+
+impl Interleave {
+    pub(crate) fn creation_option(self) -> &'static str {
+        match self {
+            Interleave::Pixel => "INTERLEAVE=PIXEL",
+            Interleave::Band => "INTERLEAVE=BAND",
+        }
+    }
+}
+
+impl FromStr for Interleave {
+    type Err = GeoTiffError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "pixel" => Ok(Interleave::Pixel),
+            "band" => Ok(Interleave::Band),
+            _ => Err(GeoTiffError::InvalidParameter(format!(
+                "Unknown interleave '{}' (expected pixel or band)",
+                s
+            ))),
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use crate::error::{last_gdal_error, GeoTiffError, Result};
+use gdal::errors::GdalError;
+use gdal::raster::RasterBand;
+use gdal_sys::{CPLErr, GDALRATFieldType, GDALRATFieldUsage, GDALRATTableType};
+use std::ffi::CString;
+
+/// The type of the values in a raster attribute table column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Integer,
+    Real,
+    String,
+}
+
+impl FieldType {
+    fn c_type(self) -> GDALRATFieldType::Type {
+        match self {
+            FieldType::Integer => GDALRATFieldType::GFT_Integer,
+            FieldType::Real => GDALRATFieldType::GFT_Real,
+            FieldType::String => GDALRATFieldType::GFT_String,
+        }
+    }
+}
+
+/// What a raster attribute table column describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldUsage {
+    /// General purpose column.
+    Generic,
+    /// Number of pixels in the row's class.
+    PixelCount,
+    /// Class name.
+    Name,
+    /// Smallest pixel value of the row's class.
+    Min,
+    /// Largest pixel value of the row's class.
+    Max,
+    /// Pixel value of the row's class.
+    MinMax,
+    /// Red component of the class colour, 0 to 255.
+    Red,
+    /// Green component of the class colour, 0 to 255.
+    Green,
+    /// Blue component of the class colour, 0 to 255.
+    Blue,
+    /// Opacity of the class colour, from 0 (transparent) to 255 (opaque).
+    Alpha,
+}
+
+impl FieldUsage {
+    fn c_usage(self) -> GDALRATFieldUsage::Type {
+        match self {
+            FieldUsage::Generic => GDALRATFieldUsage::GFU_Generic,
+            FieldUsage::PixelCount => GDALRATFieldUsage::GFU_PixelCount,
+            FieldUsage::Name => GDALRATFieldUsage::GFU_Name,
+            FieldUsage::Min => GDALRATFieldUsage::GFU_Min,
+            FieldUsage::Max => GDALRATFieldUsage::GFU_Max,
+            FieldUsage::MinMax => GDALRATFieldUsage::GFU_MinMax,
+            FieldUsage::Red => GDALRATFieldUsage::GFU_Red,
+            FieldUsage::Green => GDALRATFieldUsage::GFU_Green,
+            FieldUsage::Blue => GDALRATFieldUsage::GFU_Blue,
+            FieldUsage::Alpha => GDALRATFieldUsage::GFU_Alpha,
+        }
+    }
+}
+
+/// A value in a raster attribute table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RatValue {
+    Integer(i32),
+    Real(f64),
+    String(String),
+}
+
+impl RatValue {
+    fn as_f64(&self) -> Option<f64> {
+        match *self {
+            RatValue::Integer(v) => Some(v.into()),
+            RatValue::Real(v) => Some(v),
+            RatValue::String(_) => None,
+        }
+    }
+
+    /// Integers are accepted in real columns too.
+    fn fits(&self, field_type: FieldType) -> bool {
+        matches!(
+            (self, field_type),
+            (RatValue::Integer(_), FieldType::Integer | FieldType::Real)
+                | (RatValue::Real(_), FieldType::Real)
+                | (RatValue::String(_), FieldType::String)
+        )
+    }
+}
+
+impl From<i32> for RatValue {
+    fn from(v: i32) -> Self {
+        RatValue::Integer(v)
+    }
+}
+
+impl From<f64> for RatValue {
+    fn from(v: f64) -> Self {
+        RatValue::Real(v)
+    }
+}
+
+impl From<&str> for RatValue {
+    fn from(v: &str) -> Self {
+        RatValue::String(v.to_string())
+    }
+}
+
+impl From<String> for RatValue {
+    fn from(v: String) -> Self {
+        RatValue::String(v)
+    }
+}
+
+/// A column of a [`RasterAttributeTable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatColumn {
+    pub name: String,
+    pub field_type: FieldType,
+    pub usage: FieldUsage,
+}
+
+/// A raster attribute table (RAT), describing the classes of a band.
+///
+/// Each row describes the pixels with one value, given by a
+/// [`FieldUsage::MinMax`] column, or with values in a range, given by
+/// [`FieldUsage::Min`] and [`FieldUsage::Max`] columns. GDAL stores the
+/// table in the `.aux.xml` file next to the GeoTIFF.
+///
+/// # Examples
+///
+/// ```
+/// use rasterfakers::rat::{FieldType, FieldUsage, RasterAttributeTable, RatValue};
+/// use rasterfakers::{CategoricalPattern, FakeGeoTiffBuilder};
+///
+/// let classes = RasterAttributeTable::new()
+///     .column("Value", FieldType::Integer, FieldUsage::MinMax)
+///     .column("Class", FieldType::String, FieldUsage::Name)
+///     .row([RatValue::from(1), "Forest".into()])
+///     .row([RatValue::from(2), "Water".into()])
+///     .pixel_counts("Count");
+///
+/// let geotiff = FakeGeoTiffBuilder::new()
+///     .data_generator(Box::new(CategoricalPattern::new([(1.0, 0.7), (2.0, 0.3)]).unwrap()))
+///     .attribute_table(0, classes)
+///     .output_path("classes.tiff")
+///     .build::<u8>().unwrap();
+///
+/// geotiff.write().unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RasterAttributeTable {
+    columns: Vec<RatColumn>,
+    rows: Vec<Vec<RatValue>>,
+    pixel_counts: Option<String>,
+}
+
+impl RasterAttributeTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a column.
+    pub fn column(
+        mut self,
+        name: impl Into<String>,
+        field_type: FieldType,
+        usage: FieldUsage,
+    ) -> Self {
+        self.columns.push(RatColumn {
+            name: name.into(),
+            field_type,
+            usage,
+        });
+        self
+    }
+
+    /// Adds a row, with one value per column.
+    pub fn row(mut self, values: impl IntoIterator<Item = RatValue>) -> Self {
+        self.rows.push(values.into_iter().collect());
+        self
+    }
+
+    /// Adds a last column, `name`, holding the number of pixels of each row.
+    ///
+    /// The counts are taken from the data as written, after conversion to
+    /// the band's data type, while the GeoTIFF is generated. They are real
+    /// numbers, as counts can exceed the 32-bit integers of integer
+    /// columns. Nodata pixels aren't counted, and a pixel within the ranges
+    /// of several rows counts towards the one with the highest minimum.
+    pub fn pixel_counts(mut self, name: impl Into<String>) -> Self {
+        self.pixel_counts = Some(name.into());
+        self
+    }
+
+    pub fn columns(&self) -> &[RatColumn] {
+        &self.columns
+    }
+
+    pub fn rows(&self) -> &[Vec<RatValue>] {
+        &self.rows
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        for (index, row) in self.rows.iter().enumerate() {
+            if row.len() != self.columns.len() {
+                return Err(GeoTiffError::InvalidParameter(format!(
+                    "Attribute table row {} has {} value(s), expected {}",
+                    index,
+                    row.len(),
+                    self.columns.len()
+                )));
+            }
+            for (value, column) in row.iter().zip(&self.columns) {
+                if !value.fits(column.field_type) {
+                    return Err(GeoTiffError::InvalidParameter(format!(
+                        "Attribute table row {} has {:?} in the {:?} column '{}'",
+                        index, value, column.field_type, column.name
+                    )));
+                }
+            }
+        }
+        if self.pixel_counts.is_some() && self.row_ranges().is_none() {
+            return Err(GeoTiffError::InvalidParameter(
+                "Pixel counts require a MinMax column, or Min and Max columns".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn counts_pixels(&self) -> bool {
+        self.pixel_counts.is_some()
+    }
+
+    /// The range of pixel values of each row, if the table has value columns.
+    pub(crate) fn row_ranges(&self) -> Option<Vec<(f64, f64)>> {
+        let column_of = |usage: FieldUsage| {
+            self.columns
+                .iter()
+                .position(|column| column.usage == usage && column.field_type != FieldType::String)
+        };
+        let (min, max) = match column_of(FieldUsage::MinMax) {
+            Some(column) => (column, column),
+            None => (column_of(FieldUsage::Min)?, column_of(FieldUsage::Max)?),
+        };
+        self.rows
+            .iter()
+            .map(|row| Some((row.get(min)?.as_f64()?, row.get(max)?.as_f64()?)))
+            .collect()
+    }
+
+    /// Writes the table to `band`, with the pixel counts of each row if the
+    /// table has a pixel count column.
+    pub(crate) fn write(&self, band: &RasterBand, counts: Option<&[u64]>) -> Result<()> {
+        let rat = RatHandle::new();
+        let has_ranges = self
+            .columns
+            .iter()
+            .any(|column| matches!(column.usage, FieldUsage::Min | FieldUsage::Max));
+        let table_type = if has_ranges {
+            GDALRATTableType::GRTT_ATHEMATIC
+        } else {
+            GDALRATTableType::GRTT_THEMATIC
+        };
+
+        unsafe {
+            check(gdal_sys::GDALRATSetTableType(rat.0, table_type))?;
+            for column in &self.columns {
+                let name = c_string(&column.name)?;
+                check(gdal_sys::GDALRATCreateColumn(
+                    rat.0,
+                    name.as_ptr(),
+                    column.field_type.c_type(),
+                    column.usage.c_usage(),
+                ))?;
+            }
+            if let Some(name) = &self.pixel_counts {
+                let name = c_string(name)?;
+                check(gdal_sys::GDALRATCreateColumn(
+                    rat.0,
+                    name.as_ptr(),
+                    GDALRATFieldType::GFT_Real,
+                    GDALRATFieldUsage::GFU_PixelCount,
+                ))?;
+            }
+
+            gdal_sys::GDALRATSetRowCount(rat.0, self.rows.len() as i32);
+            for (row, values) in self.rows.iter().enumerate() {
+                for (column, value) in values.iter().enumerate() {
+                    let (row, column) = (row as i32, column as i32);
+                    match value {
+                        RatValue::Integer(v) => {
+                            gdal_sys::GDALRATSetValueAsInt(rat.0, row, column, *v)
+                        }
+                        RatValue::Real(v) => {
+                            gdal_sys::GDALRATSetValueAsDouble(rat.0, row, column, *v)
+                        }
+                        RatValue::String(v) => {
+                            let v = c_string(v)?;
+                            gdal_sys::GDALRATSetValueAsString(rat.0, row, column, v.as_ptr())
+                        }
+                    }
+                }
+            }
+            if let Some(counts) = counts {
+                let column = self.columns.len() as i32;
+                for (row, &count) in counts.iter().enumerate() {
+                    gdal_sys::GDALRATSetValueAsDouble(rat.0, row as i32, column, count as f64);
+                }
+            }
+
+            // GDAL keeps a copy of the table
+            check(gdal_sys::GDALSetDefaultRAT(band.c_rasterband(), rat.0))
+        }
+    }
+}
+
+/// Counts the pixels of each row of a [`RasterAttributeTable`].
+pub(crate) struct PixelCounter {
+    /// Row ranges as `(min, max, row)`, sorted by `min`.
+    ranges: Vec<(f64, f64, usize)>,
+    /// The largest `max` of each range and those before it, to stop
+    /// searching once no earlier range can contain a value.
+    reach: Vec<f64>,
+}
+
+impl PixelCounter {
+    pub(crate) fn new(table: &RasterAttributeTable) -> Option<Self> {
+        if !table.counts_pixels() {
+            return None;
+        }
+        let mut ranges: Vec<_> = table
+            .row_ranges()?
+            .into_iter()
+            .enumerate()
+            .map(|(row, (min, max))| (min, max, row))
+            .collect();
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let reach = ranges
+            .iter()
+            .scan(f64::NEG_INFINITY, |reach, &(_, max, _)| {
+                *reach = reach.max(max);
+                Some(*reach)
+            })
+            .collect();
+        Some(Self { ranges, reach })
+    }
+
+    pub(crate) fn rows(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Counts `values` towards the row whose range contains them. When
+    /// ranges touch or overlap, as in `[0, 10]` and `[10, 20]`, or nest, as
+    /// in `[0, 100]` and `[10, 20]`, a value goes to the range with the
+    /// highest minimum among those containing it.
+    pub(crate) fn count(&self, values: impl Iterator<Item = f64>, counts: &mut [u64]) {
+        for value in values {
+            let candidates = self.ranges.partition_point(|&(min, _, _)| min <= value);
+            let row = (0..candidates)
+                .rev()
+                .take_while(|&i| self.reach[i] >= value)
+                .find(|&i| self.ranges[i].1 >= value)
+                .map(|i| self.ranges[i].2);
+            if let Some(row) = row {
+                counts[row] += 1;
+            }
+        }
+    }
+}
+
+/// Owns a GDAL raster attribute table until it's dropped.
+struct RatHandle(gdal_sys::GDALRasterAttributeTableH);
+
+impl RatHandle {
+    fn new() -> Self {
+        Self(unsafe { gdal_sys::GDALCreateRasterAttributeTable() })
+    }
+}
+
+impl Drop for RatHandle {
+    fn drop(&mut self) {
+        unsafe { gdal_sys::GDALDestroyRasterAttributeTable(self.0) }
+    }
+}
+
+fn check(error: CPLErr::Type) -> Result<()> {
+    match error {
+        CPLErr::CE_None => Ok(()),
+        _ => Err(last_gdal_error(error)),
+    }
+}
+
+fn c_string(s: &str) -> Result<CString> {
+    Ok(CString::new(s).map_err(GdalError::from)?)
+}
@@ -1,4 +1,10 @@
-use rasterfakers::{FakeGeoTiffBuilder, GeoTiffError};
+use rasterfakers::colors::{Color, ColorRamp, ColorTable};
+use rasterfakers::conversions::Complex;
+use rasterfakers::rat::{FieldType, FieldUsage, RasterAttributeTable, RatValue};
+use rasterfakers::{
+    BigTiff, Compression, ConstantPattern, FakeGeoTiffBuilder, GeoTiffError, HoleMask, Interleave,
+    Layout, OverviewStorage, Overviews, Predictor, Resampling,
+};
 
 #[test]
 fn test_zero_dimensions() {
@@ -62,3 +68,644 @@ fn test_valid_builder() {
         .build::<f32>();
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_nodata_out_of_range() {
+    let result = FakeGeoTiffBuilder::new()
+        .nodata(-9999.0)
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "Nodata value -9999 cannot be represented by u8");
+        }
+        _ => panic!("Expected InvalidParameter error for out-of-range nodata"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .nodata(f64::NAN)
+        .output_path("test.tiff")
+        .build::<i32>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+}
+
+#[test]
+fn test_valid_nodata() {
+    let result = FakeGeoTiffBuilder::new()
+        .bands(2)
+        .unwrap()
+        .nodata(-9999.0)
+        .band_nodata(1, f64::NAN)
+        .output_path("test.tiff")
+        .build::<f32>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_band_nodata_out_of_range() {
+    let result = FakeGeoTiffBuilder::new()
+        .bands(2)
+        .unwrap()
+        .band_nodata(2, 0.0)
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "Nodata band index 2 is out of range for 2 band(s)");
+        }
+        _ => panic!("Expected InvalidParameter error for out-of-range band"),
+    }
+}
+
+#[test]
+fn test_nodata_holes_require_nodata() {
+    let result = FakeGeoTiffBuilder::new()
+        .nodata_holes(HoleMask::Scattered { fraction: 0.1 }, 0)
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::MissingField(msg)) => {
+            assert_eq!(
+                msg,
+                "Nodata value must be specified for every band to inject nodata holes"
+            );
+        }
+        _ => panic!("Expected MissingField error for nodata holes without nodata"),
+    }
+}
+
+#[test]
+fn test_nodata_holes_invalid_fraction() {
+    let result = FakeGeoTiffBuilder::new()
+        .nodata(0.0)
+        .nodata_holes(HoleMask::Rows { fraction: 1.5 }, 0)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+}
+
+#[test]
+fn test_nodata_holes_zero_size() {
+    // A size of 0 is treated as 1, as in NoDataHoles itself
+    for mask in [
+        HoleMask::Blobs {
+            fraction: 0.1,
+            size: 0,
+        },
+        HoleMask::Tiles {
+            fraction: 0.1,
+            size: 0,
+        },
+    ] {
+        let result = FakeGeoTiffBuilder::new()
+            .nodata(0.0)
+            .nodata_holes(mask.clone(), 0)
+            .output_path("test.tiff")
+            .build::<u8>();
+        assert!(result.is_ok(), "{:?}", mask);
+    }
+}
+
+#[test]
+fn test_compression_data_type() {
+    let result = FakeGeoTiffBuilder::new()
+        .compression(Compression::Jpeg)
+        .output_path("test.tiff")
+        .build::<u16>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "JPEG compression only supports u8 data, not u16");
+        }
+        _ => panic!("Expected InvalidParameter error for JPEG with u16"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .compression(Compression::Jpeg)
+        .compression_level(90)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_compression_level() {
+    let result = FakeGeoTiffBuilder::new()
+        .compression(Compression::Deflate)
+        .compression_level(13)
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "DEFLATE compression level must be between 1 and 12, got 13"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for out-of-range level"),
+    }
+
+    // Levels need a codec that supports them, including the implicit defaults
+    for builder in [
+        FakeGeoTiffBuilder::new().compression(Compression::Lzw),
+        FakeGeoTiffBuilder::new(),
+    ] {
+        let result = builder
+            .compression_level(5)
+            .output_path("test.tiff")
+            .build::<u8>();
+        assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+    }
+}
+
+#[test]
+fn test_predictor() {
+    let result = FakeGeoTiffBuilder::new()
+        .compression(Compression::Zstd)
+        .predictor(Predictor::FloatingPoint)
+        .output_path("test.tiff")
+        .build::<i16>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "Floating point predictor requires floating point data, not i16"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for floating point predictor"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .compression(Compression::Packbits)
+        .predictor(Predictor::Horizontal)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    // COGs default to LZW, which supports predictors
+    let result = FakeGeoTiffBuilder::new()
+        .cloud_optimized(true)
+        .predictor(Predictor::Horizontal)
+        .output_path("test.tiff")
+        .build::<u16>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_parse_options() {
+    assert_eq!("zstd".parse::<Compression>().unwrap(), Compression::Zstd);
+    assert_eq!("LZW".parse::<Compression>().unwrap(), Compression::Lzw);
+    assert_eq!(
+        "floating-point".parse::<Predictor>().unwrap(),
+        Predictor::FloatingPoint
+    );
+    assert_eq!("if-safer".parse::<BigTiff>().unwrap(), BigTiff::IfSafer);
+    assert!("brotli".parse::<Compression>().is_err());
+}
+
+#[test]
+fn test_layout() {
+    let result = FakeGeoTiffBuilder::new()
+        .layout(Layout::Tiles {
+            width: 500,
+            height: 512,
+        })
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidBlockSize(msg)) => {
+            assert_eq!(
+                msg,
+                "Tile size must be a non-zero multiple of 16, got 500x512"
+            );
+        }
+        _ => panic!("Expected InvalidBlockSize error for 500x512 tiles"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .layout(Layout::Strips { rows_per_strip: 0 })
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidBlockSize(_))));
+
+    // Strips may have any number of rows, but not in a COG
+    let result = FakeGeoTiffBuilder::new()
+        .layout(Layout::Strips { rows_per_strip: 7 })
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(result.is_ok());
+
+    let result = FakeGeoTiffBuilder::new()
+        .cloud_optimized(true)
+        .layout(Layout::Strips { rows_per_strip: 7 })
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let result = FakeGeoTiffBuilder::new()
+        .cloud_optimized(true)
+        .layout(Layout::Tiles {
+            width: 1024,
+            height: 1024,
+        })
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_cloud_optimized_options() {
+    let result = FakeGeoTiffBuilder::new()
+        .cloud_optimized(true)
+        .layout(Layout::Tiles {
+            width: 512,
+            height: 256,
+        })
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidBlockSize(msg)) => {
+            assert_eq!(
+                msg,
+                "Cloud optimized GeoTIFFs need square tiles, got 512x256"
+            );
+        }
+        _ => panic!("Expected InvalidBlockSize error for non-square COG tiles"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .cloud_optimized(true)
+        .compression(Compression::Packbits)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+}
+
+#[test]
+fn test_overview_factors() {
+    let auto = Overviews::Auto { min_size: 256 };
+    assert_eq!(auto.factors(1024, 600), vec![2, 4]);
+    assert_eq!(auto.factors(1025, 600), vec![2, 4, 8]);
+    assert!(auto.factors(64, 64).is_empty());
+    assert!(Overviews::None.factors(4096, 4096).is_empty());
+
+    assert_eq!("auto".parse::<Overviews>().unwrap(), auto);
+    assert_eq!(
+        "auto:128".parse::<Overviews>().unwrap(),
+        Overviews::Auto { min_size: 128 }
+    );
+    assert_eq!(
+        "2, 4,8".parse::<Overviews>().unwrap(),
+        Overviews::Factors(vec![2, 4, 8])
+    );
+    assert!("2,x".parse::<Overviews>().is_err());
+    assert_eq!("gauss".parse::<Resampling>().unwrap(), Resampling::Gauss);
+}
+
+#[test]
+fn test_overview_validation() {
+    let result = FakeGeoTiffBuilder::new()
+        .dimensions(64, 64)
+        .unwrap()
+        .overviews(Overviews::Factors(vec![2, 128]))
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "Overview factor 128 must be between 2 and the raster size of 64x64"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for overview factor"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .overviews(Overviews::Auto { min_size: 0 })
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let result = FakeGeoTiffBuilder::new()
+        .cloud_optimized(true)
+        .overview_storage(OverviewStorage::External)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let result = FakeGeoTiffBuilder::new()
+        .overviews(Overviews::Factors(vec![2, 4]))
+        .overview_resampling(Resampling::Average)
+        .overview_storage(OverviewStorage::External)
+        .output_path("test.tiff")
+        .build::<f32>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_interleave() {
+    assert_eq!("band".parse::<Interleave>().unwrap(), Interleave::Band);
+    assert_eq!("PIXEL".parse::<Interleave>().unwrap(), Interleave::Pixel);
+    assert!("line".parse::<Interleave>().is_err());
+
+    let cog = |interleave| {
+        FakeGeoTiffBuilder::new()
+            .bands(3)
+            .unwrap()
+            .interleave(interleave)
+            .cloud_optimized(true)
+            .output_path("test.tiff")
+            .build::<u8>()
+    };
+    assert!(cog(Interleave::Pixel).is_ok());
+    match cog(Interleave::Band) {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "Cloud optimized GeoTIFFs can't be band interleaved")
+        }
+        _ => panic!("Expected InvalidParameter error for a band interleaved COG"),
+    }
+}
+
+#[test]
+fn test_complex_data_types() {
+    let result = FakeGeoTiffBuilder::new()
+        .imaginary_generator(Box::new(ConstantPattern(1.0)))
+        .output_path("test.tiff")
+        .build::<f32>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "An imaginary generator requires a complex data type, not f32"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for imaginary generator on f32"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .compression(Compression::Deflate)
+        .predictor(Predictor::Horizontal)
+        .output_path("test.tiff")
+        .build::<Complex<i16>>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let result = FakeGeoTiffBuilder::new()
+        .nodata(-9999.0)
+        .imaginary_generator(Box::new(ConstantPattern(1.0)))
+        .compression(Compression::Zstd)
+        .output_path("test.tiff")
+        .build::<Complex<f32>>();
+    assert!(result.is_ok());
+
+    // Nodata must fit the new integer types too
+    let result = FakeGeoTiffBuilder::new()
+        .nodata(-129.0)
+        .output_path("test.tiff")
+        .build::<i8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+    let result = FakeGeoTiffBuilder::new()
+        .nodata(-1.0)
+        .output_path("test.tiff")
+        .build::<i64>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_band_generator_out_of_range() {
+    let result = FakeGeoTiffBuilder::new()
+        .bands(3)
+        .unwrap()
+        .band_generator(2, Box::new(ConstantPattern(1.0)))
+        .band_generator(3, Box::new(ConstantPattern(2.0)))
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "Generator band index 3 is out of range for 3 band(s)");
+        }
+        _ => panic!("Expected InvalidParameter error for band generator index"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .bands(3)
+        .unwrap()
+        .band_generator(2, Box::new(ConstantPattern(1.0)))
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_data_fn() {
+    let result = FakeGeoTiffBuilder::new()
+        .data_fn(|x, y, _band| (x ^ y) as f64)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_color_table() {
+    let colors = ColorTable::new()
+        .color(1, Color::rgb(255, 0, 0))
+        .color(2, Color::rgb(0, 0, 255));
+
+    let result = FakeGeoTiffBuilder::new()
+        .color_table(colors.clone())
+        .category_names(0, [(1, "Urban"), (2, "Water")])
+        .output_path("test.tiff")
+        .build::<u16>();
+    assert!(result.is_ok());
+
+    let result = FakeGeoTiffBuilder::new()
+        .color_table(colors.clone())
+        .output_path("test.tiff")
+        .build::<f32>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "Color tables require u8 or u16 data, not f32");
+        }
+        _ => panic!("Expected InvalidParameter error for a color table on f32"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .bands(3)
+        .unwrap()
+        .color_table(colors)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let result = FakeGeoTiffBuilder::new()
+        .color_table(ColorTable::new().color(256, Color::rgb(0, 0, 0)))
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let result = FakeGeoTiffBuilder::new()
+        .category_names(1, [(0, "Background")])
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "Category names band index 1 is out of range for 1 band(s)"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for category names band index"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .category_names(0, [(1, "Forest"), (4_000_000_000, "Unreachable")])
+        .output_path("test.tiff")
+        .build::<u16>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "Category name value 4000000000 cannot be represented by u16"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for a category value out of range"),
+    }
+}
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(
+        "#1a9850".parse::<Color>().unwrap(),
+        Color::rgb(0x1a, 0x98, 0x50)
+    );
+    assert_eq!(
+        "FF000080".parse::<Color>().unwrap(),
+        Color::rgba(255, 0, 0, 128)
+    );
+    for invalid in ["", "#fff", "#12345g", "#1234567", "red"] {
+        assert!(
+            matches!(
+                invalid.parse::<Color>(),
+                Err(GeoTiffError::InvalidParameter(_))
+            ),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_generated_color_tables() {
+    let viridis = ColorTable::ramp(ColorRamp::Viridis, 256);
+    assert_eq!(viridis.iter().count(), 256);
+    assert_eq!(viridis.get(0), Some(Color::rgb(0x44, 0x01, 0x54)));
+    assert_eq!(viridis.get(255), Some(Color::rgb(0xfd, 0xe7, 0x25)));
+    assert_eq!(viridis.get(256), None);
+
+    let gray = ColorTable::ramp(ColorRamp::Grayscale, 3);
+    let grays: Vec<_> = gray.iter().map(|(_, color)| color).collect();
+    assert_eq!(
+        grays,
+        [
+            Color::rgb(0, 0, 0),
+            Color::rgb(128, 128, 128),
+            Color::rgb(255, 255, 255)
+        ]
+    );
+    assert_eq!(
+        ColorTable::ramp(ColorRamp::Magma, 100_000).iter().count(),
+        65536
+    );
+
+    let distinct = ColorTable::distinct(24, 42);
+    let mut colors: Vec<_> = distinct.iter().map(|(_, color)| color).collect();
+    assert!(colors.iter().all(|color| color.a == 255));
+    colors.sort_by_key(|color| (color.r, color.g, color.b));
+    colors.dedup();
+    assert_eq!(colors.len(), 24);
+    assert_eq!(distinct, ColorTable::distinct(24, 42));
+    assert_ne!(distinct, ColorTable::distinct(24, 43));
+
+    assert_eq!("Plasma".parse::<ColorRamp>().unwrap(), ColorRamp::Plasma);
+    assert!(matches!(
+        "rainbow".parse::<ColorRamp>(),
+        Err(GeoTiffError::InvalidParameter(_))
+    ));
+}
+
+#[test]
+fn test_attribute_table() {
+    let classes = RasterAttributeTable::new()
+        .column("Value", FieldType::Integer, FieldUsage::MinMax)
+        .column("Name", FieldType::String, FieldUsage::Name)
+        .column("Area", FieldType::Real, FieldUsage::Generic)
+        .row([RatValue::from(1), "Forest".into(), 2.5.into()])
+        .row([RatValue::from(2), "Water".into(), RatValue::from(4)])
+        .pixel_counts("Count");
+
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(0, classes.clone())
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(result.is_ok());
+
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(1, classes.clone())
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(
+                msg,
+                "Attribute table band index 1 is out of range for 1 band(s)"
+            );
+        }
+        _ => panic!("Expected InvalidParameter error for attribute table band index"),
+    }
+
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(0, classes.clone().row([RatValue::from(3)]))
+        .output_path("test.tiff")
+        .build::<u8>();
+    match result {
+        Err(GeoTiffError::InvalidParameter(msg)) => {
+            assert_eq!(msg, "Attribute table row 2 has 1 value(s), expected 3");
+        }
+        _ => panic!("Expected InvalidParameter error for a short row"),
+    }
+
+    let mistyped = classes
+        .clone()
+        .row([RatValue::from(3), RatValue::from(3), 1.0.into()]);
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(0, mistyped)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    // Pixel counts need to know which values each row covers
+    let names_only = RasterAttributeTable::new()
+        .column("Name", FieldType::String, FieldUsage::Name)
+        .row(["Forest".into()])
+        .pixel_counts("Count");
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(0, names_only)
+        .output_path("test.tiff")
+        .build::<u8>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+
+    let ranges = RasterAttributeTable::new()
+        .column("Min", FieldType::Real, FieldUsage::Min)
+        .column("Max", FieldType::Real, FieldUsage::Max)
+        .row([0.0.into(), 10.0.into()])
+        .pixel_counts("Count");
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(0, ranges.clone())
+        .output_path("test.tiff")
+        .build::<f32>();
+    assert!(result.is_ok());
+
+    let result = FakeGeoTiffBuilder::new()
+        .attribute_table(0, ranges)
+        .output_path("test.tiff")
+        .build::<Complex<f32>>();
+    assert!(matches!(result, Err(GeoTiffError::InvalidParameter(_))));
+}
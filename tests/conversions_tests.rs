@@ -0,0 +1,161 @@
+use rasterfakers::conversions::{Complex, ConversionPolicy, ConvertFromF64};
+
+#[test]
+fn test_i8_conversion() {
+    assert_eq!(i8::convert_from_f64(-12.7), -12);
+    assert_eq!(i8::convert_from_f64(-200.0), i8::MIN);
+    assert_eq!(i8::convert_from_f64(200.0), i8::MAX);
+    assert!(i8::can_represent(-128.0));
+    assert!(!i8::can_represent(128.0));
+}
+
+#[test]
+fn test_64_bit_conversion() {
+    assert_eq!(u64::convert_from_f64(-1.0), 0);
+    assert_eq!(u64::convert_from_f64(1e20), u64::MAX);
+    assert_eq!(i64::convert_from_f64(-1e20), i64::MIN);
+    assert_eq!(i64::convert_from_f64(9_007_199_254_740_992.0), 1 << 53);
+
+    // u64::MAX and i64::MAX round up to out of range values as f64
+    assert!(!u64::can_represent(u64::MAX as f64));
+    assert!(!i64::can_represent(i64::MAX as f64));
+    assert!(i64::can_represent(i64::MIN as f64));
+    assert!(u64::can_represent(0.0));
+}
+
+#[test]
+fn test_f32_conversion() {
+    assert_eq!(f32::convert_from_f64(2.5), 2.5);
+    assert!(f32::convert_from_f64(f64::NAN).is_nan());
+    // Infinite nodata values must be written as declared
+    assert_eq!(f32::convert_from_f64(f64::NEG_INFINITY), f32::NEG_INFINITY);
+    assert!(f32::can_represent(f64::NEG_INFINITY));
+    assert_eq!(f32::stored_value(f64::INFINITY), f64::INFINITY);
+}
+
+#[test]
+fn test_complex_conversion() {
+    assert_eq!(
+        Complex::<i16>::convert_from_parts(1.5, -40000.0),
+        Complex {
+            re: 1,
+            im: i16::MIN
+        }
+    );
+    assert_eq!(
+        Complex::<f32>::convert_from_f64(2.5),
+        Complex { re: 2.5, im: 0.0 }
+    );
+    assert!(!Complex::<i16>::can_represent(0.5));
+    assert!(Complex::<f64>::can_represent(f64::NAN));
+
+    // Real types ignore the imaginary part
+    assert_eq!(u8::convert_from_parts(3.0, 4.0), 3);
+}
+
+#[test]
+fn test_conversion_policies() {
+    let range = (0.0, 0.0);
+    assert_eq!(
+        ConversionPolicy::Saturate.prepare::<u8>(12.7, range),
+        Some(12.7)
+    );
+    assert_eq!(u8::convert_from_f64(12.7), 12);
+    assert_eq!(
+        ConversionPolicy::Round.prepare::<u8>(12.7, range),
+        Some(13.0)
+    );
+
+    assert_eq!(
+        ConversionPolicy::Wrap.prepare::<u8>(256.0, range),
+        Some(0.0)
+    );
+    assert_eq!(
+        ConversionPolicy::Wrap.prepare::<u8>(-1.0, range),
+        Some(255.0)
+    );
+    assert_eq!(
+        ConversionPolicy::Wrap.prepare::<i8>(130.0, range),
+        Some(-126.0)
+    );
+    assert_eq!(
+        ConversionPolicy::Wrap.prepare::<i16>(-5.0, range),
+        Some(-5.0)
+    );
+
+    assert_eq!(
+        ConversionPolicy::Error.prepare::<u8>(255.4, range),
+        Some(255.0)
+    );
+    assert_eq!(ConversionPolicy::Error.prepare::<u8>(255.6, range), None);
+    assert_eq!(
+        ConversionPolicy::Error.prepare::<i16>(f64::NAN, range),
+        None
+    );
+    assert_eq!(ConversionPolicy::Error.prepare::<f32>(1e39, range), None);
+    assert!(ConversionPolicy::Error
+        .prepare::<f32>(f64::NAN, range)
+        .unwrap()
+        .is_nan());
+
+    // Floating point types keep their values, except under Error
+    assert_eq!(
+        ConversionPolicy::Round.prepare::<f32>(0.25, range),
+        Some(0.25)
+    );
+    assert_eq!(
+        ConversionPolicy::Rescale.prepare::<f64>(7.5, range),
+        Some(7.5)
+    );
+}
+
+#[test]
+fn test_rescale_policy() {
+    let range = (-43758.0, 43758.0);
+    let rescale = |v| ConversionPolicy::Rescale.prepare::<u8>(v, range);
+    assert_eq!(rescale(-43758.0), Some(0.0));
+    assert_eq!(rescale(0.0), Some(128.0));
+    assert_eq!(rescale(43758.0), Some(255.0));
+
+    let rescale = |v| ConversionPolicy::Rescale.prepare::<i16>(v, (0.0, 1.0));
+    assert_eq!(rescale(0.0), Some(-32768.0));
+    assert_eq!(rescale(1.0), Some(32767.0));
+
+    // A constant raster maps to the middle of the range, and NaN to the bottom
+    assert_eq!(
+        ConversionPolicy::Rescale.prepare::<u16>(5.0, (5.0, 5.0)),
+        Some(32768.0)
+    );
+    assert_eq!(
+        ConversionPolicy::Rescale.prepare::<u8>(f64::NAN, range),
+        Some(0.0)
+    );
+
+    // Floating point values are kept whatever the range
+    for range in [(0.0, 1.0), (5.0, 5.0)] {
+        assert_eq!(
+            ConversionPolicy::Rescale.prepare::<f32>(300.0, range),
+            Some(300.0)
+        );
+    }
+    assert_eq!(
+        "rescale".parse::<ConversionPolicy>().unwrap(),
+        ConversionPolicy::Rescale
+    );
+}
+
+#[test]
+fn test_stored_value() {
+    // Matches what convert_from_f64 writes, for counting pixels
+    for v in [-3.7, 12.5, 300.0, f64::NAN, f64::NEG_INFINITY] {
+        assert_eq!(u8::stored_value(v), u8::convert_from_f64(v) as f64, "{}", v);
+        assert_eq!(
+            i16::stored_value(v),
+            i16::convert_from_f64(v) as f64,
+            "{}",
+            v
+        );
+    }
+    assert_eq!(f32::stored_value(0.1), 0.1f32 as f64);
+    assert_eq!(f64::stored_value(0.1), 0.1);
+}
@@ -0,0 +1,81 @@
+use rasterfakers::patterns::{DataGenerator, PixelContext, RasterInfo};
+use rasterfakers::{ExpressionPattern, GeoTiffError, GeoTransform};
+
+fn eval(source: &str) -> f64 {
+    ExpressionPattern::parse(source)
+        .unwrap_or_else(|e| panic!("failed to parse '{}': {}", source, e))
+        .generate(0, 0, 0)
+}
+
+#[test]
+fn test_expression_arithmetic() {
+    assert_eq!(eval("1 + 2 * 3"), 7.0);
+    assert_eq!(eval("(1 + 2) * 3"), 9.0);
+    assert_eq!(eval("10 - 4 - 3"), 3.0);
+    assert_eq!(eval("7 % 4"), 3.0);
+    assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+    assert_eq!(eval("-2 ^ 2"), -4.0);
+    assert_eq!(eval("2 ^ -1"), 0.5);
+    assert_eq!(eval("1.5e2 + .5"), 150.5);
+
+    // Comparisons give 1 or 0, and bind loosest
+    assert_eq!(eval("1 + 1 == 2"), 1.0);
+    assert_eq!(eval("3 < 2"), 0.0);
+    assert_eq!(eval("if(2 >= 2, 10, 20)"), 10.0);
+
+    assert_eq!(eval("max(1, min(5, 3))"), 3.0);
+    assert_eq!(eval("clamp(300, 0, 255)"), 255.0);
+    assert_eq!(eval("hypot(3, 4)"), 5.0);
+    assert_eq!(eval("sign(-3) + abs(-2) + floor(1.7)"), 2.0);
+    assert!((eval("sin(pi / 2) + ln(e)") - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_expression_variables() {
+    let pattern = ExpressionPattern::parse("x * 100 + y * 10 + band").unwrap();
+    assert_eq!(pattern.generate(3, 4, 2), 342.0);
+
+    let raster = RasterInfo {
+        width: 20,
+        height: 10,
+        bands: 3,
+        geotransform: GeoTransform {
+            x_min: 10.0,
+            pixel_width: 0.5,
+            rotation_x: 0.0,
+            y_max: 60.0,
+            rotation_y: 0.0,
+            pixel_height: -0.5,
+        },
+    };
+    let ctx = PixelContext::new(1, 2, 1, &raster);
+    let eval_at = |source: &str| ExpressionPattern::parse(source).unwrap().generate_at(&ctx);
+    assert_eq!(eval_at("map_x"), 10.75);
+    assert_eq!(eval_at("map_y"), 58.75);
+    assert_eq!(eval_at("width * height"), 200.0);
+    assert_eq!(eval_at("bands - band"), 2.0);
+}
+
+#[test]
+fn test_expression_parse_errors() {
+    for source in [
+        "",
+        "1 +",
+        "sin(x",
+        "(x + 1",
+        "x y",
+        "x = 1",
+        "foo(x)",
+        "unknown + 1",
+        "min(x)",
+        "sin(x, y)",
+        "x $ 2",
+    ] {
+        match ExpressionPattern::parse(source) {
+            Err(GeoTiffError::InvalidParameter(message)) => {
+                assert!(message.contains("position"), "{}", message)
+            }
+            other => panic!("'{}' should not parse: {:?}", source, other.map(|_| ())),
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use rasterfakers::patterns::{DataGenerator, GradientPattern, NoisePattern, SineWavePattern};
+use rasterfakers::patterns::{
+    CategoricalPattern, CheckerboardPattern, CoherentNoise, ConstantPattern, DataGenerator,
+    ExponentialNoise, Fbm, GaussianNoise, GradientPattern, GridPattern, HoleMask, MapRange,
+    NoDataHoles, NoisePattern, PerlinNoise, PixelContext, PoissonNoise, RadialGradientPattern,
+    RasterInfo, RingPattern, SiemensStarPattern, SimplexNoise, SineWavePattern, StripePattern,
+    TerrainPattern, UniformNoise, Window,
+};
+use rasterfakers::{GeoTiffError, GeoTransform, PatternExt, PerBand};
 
 #[test]
 fn test_gradient_pattern() {
@@ -89,3 +96,735 @@ fn test_custom_pattern() {
     assert_eq!(pattern.generate(0, 5, 2), 0.0);
     assert_eq!(pattern.generate(10, 10, 1), 200.0);
 }
+
+#[test]
+fn test_nodata_holes() {
+    let pattern = NoDataHoles::new(
+        GradientPattern,
+        HoleMask::Rect {
+            x: 2,
+            y: 3,
+            width: 4,
+            height: 5,
+        },
+        -9999.0,
+    );
+
+    // Holes only inside the rectangle
+    assert_eq!(pattern.generate(2, 3, 0), -9999.0);
+    assert_eq!(pattern.generate(5, 7, 1), -9999.0);
+    assert_eq!(pattern.generate(6, 7, 0), 13.0);
+    assert_eq!(pattern.generate(1, 3, 0), 4.0);
+
+    // Per-band nodata values, with the last value reused
+    let pattern = NoDataHoles::new(GradientPattern, HoleMask::Rows { fraction: 1.0 }, 0.0)
+        .band_nodata(vec![1.0, 2.0]);
+    assert_eq!(pattern.generate(0, 0, 0), 1.0);
+    assert_eq!(pattern.generate(0, 0, 1), 2.0);
+    assert_eq!(pattern.generate(0, 0, 5), 2.0);
+
+    // A size of 0 behaves like single pixel blobs and tiles
+    for mask in [
+        HoleMask::Blobs {
+            fraction: 0.5,
+            size: 0,
+        },
+        HoleMask::Tiles {
+            fraction: 0.5,
+            size: 0,
+        },
+    ] {
+        let pattern = NoDataHoles::new(GradientPattern, mask, -1.0);
+        for (x, y) in [(0, 0), (3, 7), (100, 1)] {
+            let value = pattern.generate(x, y, 0);
+            assert!(value == -1.0 || value == (x + y) as f64, "{}", value);
+        }
+    }
+}
+
+#[test]
+fn test_nodata_holes_fraction_and_seed() {
+    for mask in [
+        HoleMask::Scattered { fraction: 0.25 },
+        HoleMask::Blobs {
+            fraction: 0.25,
+            size: 16,
+        },
+        HoleMask::Tiles {
+            fraction: 0.25,
+            size: 8,
+        },
+    ] {
+        let pattern = NoDataHoles::new(GradientPattern, mask.clone(), -1.0).seed(7);
+        let holes = (0..512 * 512)
+            .filter(|i| pattern.is_hole(i % 512, i / 512))
+            .count();
+        let fraction = holes as f64 / (512.0 * 512.0);
+        assert!(
+            (fraction - 0.25).abs() < 0.05,
+            "{:?} gave {}",
+            mask,
+            fraction
+        );
+
+        // Same seed gives the same mask, a different seed a different one
+        let same = NoDataHoles::new(GradientPattern, mask.clone(), -1.0).seed(7);
+        let other = NoDataHoles::new(GradientPattern, mask, -1.0).seed(8);
+        let mut differs = false;
+        for y in 0..128 {
+            for x in 0..128 {
+                assert_eq!(pattern.is_hole(x, y), same.is_hole(x, y));
+                differs |= pattern.is_hole(x, y) != other.is_hole(x, y);
+            }
+        }
+        assert!(differs);
+    }
+}
+
+fn sample_stats(pattern: &dyn DataGenerator) -> (f64, f64) {
+    let values: Vec<f64> = (0..256 * 256)
+        .map(|i| pattern.generate(i % 256, i / 256, 0))
+        .collect();
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    (mean, variance.sqrt())
+}
+
+#[test]
+fn test_random_distributions() {
+    let uniform = UniformNoise::new(10.0, 20.0).seed(1);
+    for i in 0..1000 {
+        let value = uniform.generate(i, i * 3, 0);
+        assert!((10.0..20.0).contains(&value), "value is {}", value);
+    }
+    let (mean, _) = sample_stats(&uniform);
+    assert!((mean - 15.0).abs() < 0.1, "uniform mean is {}", mean);
+
+    let (mean, std_dev) = sample_stats(&GaussianNoise::new(100.0, 15.0).seed(1));
+    assert!((mean - 100.0).abs() < 0.5, "gaussian mean is {}", mean);
+    assert!(
+        (std_dev - 15.0).abs() < 0.5,
+        "gaussian std_dev is {}",
+        std_dev
+    );
+
+    for lambda in [4.0, 100.0] {
+        let poisson = PoissonNoise::new(lambda).seed(1);
+        assert_eq!(poisson.generate(3, 4, 0).fract(), 0.0);
+        let (mean, std_dev) = sample_stats(&poisson);
+        assert!(
+            (mean - lambda).abs() < 0.1 * lambda.sqrt(),
+            "poisson mean is {}",
+            mean
+        );
+        assert!((std_dev - lambda.sqrt()).abs() < 0.1 * lambda.sqrt());
+    }
+
+    let (mean, std_dev) = sample_stats(&ExponentialNoise::new(0.5).seed(1));
+    assert!((mean - 2.0).abs() < 0.05, "exponential mean is {}", mean);
+    assert!(
+        (std_dev - 2.0).abs() < 0.05,
+        "exponential std_dev is {}",
+        std_dev
+    );
+}
+
+#[test]
+fn test_random_seeds() {
+    let a = GaussianNoise::new(0.0, 1.0).seed(42);
+    let b = GaussianNoise::new(0.0, 1.0).seed(42);
+    let c = GaussianNoise::new(0.0, 1.0).seed(43);
+
+    assert_eq!(a.generate(10, 20, 1), b.generate(10, 20, 1));
+    assert_ne!(a.generate(10, 20, 1), c.generate(10, 20, 1));
+    assert_ne!(a.generate(10, 20, 0), a.generate(10, 20, 1));
+}
+
+#[test]
+fn test_random_golden_values() {
+    // These values must never change: golden-file fixtures depend on them.
+    assert_eq!(
+        UniformNoise::new(0.0, 1.0).seed(42).generate(7, 11, 2),
+        0.2288060406594209
+    );
+    assert_eq!(
+        GaussianNoise::new(0.0, 1.0).seed(42).generate(7, 11, 2),
+        -0.2646213270757692
+    );
+    assert_eq!(PoissonNoise::new(5.0).seed(42).generate(7, 11, 2), 9.0);
+    assert_eq!(
+        ExponentialNoise::new(1.0).seed(42).generate(7, 11, 2),
+        0.2598153685217435
+    );
+}
+
+#[test]
+fn test_coherent_noise() {
+    let patterns: Vec<Box<dyn DataGenerator>> = vec![
+        Box::new(PerlinNoise::new(0.05).seed(3)),
+        Box::new(SimplexNoise::new(0.05).seed(3)),
+        Box::new(
+            Fbm::new(PerlinNoise::new(1.0).seed(3))
+                .frequency(0.05)
+                .octaves(5),
+        ),
+        Box::new(Fbm::new(SimplexNoise::new(1.0).seed(3)).frequency(0.05)),
+    ];
+
+    for pattern in &patterns {
+        let (mean, std_dev) = sample_stats(pattern.as_ref());
+        assert!(mean.abs() < 0.1, "mean is {}", mean);
+        assert!(std_dev > 0.1, "std_dev is {}", std_dev);
+
+        // Neighbouring pixels are strongly correlated, unlike white noise
+        let mut max_step: f64 = 0.0;
+        for y in 0..128 {
+            for x in 0..128 {
+                let value = pattern.generate(x, y, 0);
+                assert!((-1.0..=1.0).contains(&value), "value is {}", value);
+                max_step = max_step.max((pattern.generate(x + 1, y, 0) - value).abs());
+            }
+        }
+        assert!(max_step < 0.5, "max_step is {}", max_step);
+
+        assert_ne!(pattern.generate(10, 10, 0), pattern.generate(10, 10, 1));
+    }
+}
+
+#[test]
+fn test_fbm_octaves() {
+    let noise = PerlinNoise::new(1.0).seed(9);
+    let fbm = Fbm::new(PerlinNoise::new(1.0).seed(9))
+        .frequency(0.1)
+        .octaves(1);
+    assert_eq!(
+        fbm.generate(13, 17, 0),
+        noise.sample(13.0 * 0.1, 17.0 * 0.1, 0)
+    );
+
+    // Extra octaves add detail on top of the first one, unless they have
+    // no amplitude
+    let fbm = |octaves, persistence| {
+        Fbm::new(PerlinNoise::new(1.0).seed(9))
+            .frequency(0.1)
+            .octaves(octaves)
+            .persistence(persistence)
+    };
+    let (one, six, silent) = (fbm(1, 0.5), fbm(6, 0.5), fbm(6, 0.0));
+    let mut differs = false;
+    for (x, y) in [(13, 17), (40, 50), (3, 91), (77, 8)] {
+        assert_eq!(silent.generate(x, y, 0), one.generate(x, y, 0));
+        differs |= six.generate(x, y, 0) != one.generate(x, y, 0);
+    }
+    assert!(differs);
+
+    let a = Fbm::new(PerlinNoise::new(1.0).seed(9)).octaves(6);
+    let b = Fbm::new(PerlinNoise::new(1.0).seed(10)).octaves(6);
+    assert_ne!(a.generate(40, 50, 0), b.generate(40, 50, 0));
+}
+
+#[test]
+fn test_terrain_pattern() {
+    let make = |seed| {
+        TerrainPattern::new(-50.0, 1500.0)
+            .roughness(0.7)
+            .feature_size(32.0)
+            .seed(seed)
+    };
+    let terrain = make(5);
+
+    let values: Vec<f64> = (0..256 * 256)
+        .map(|i| terrain.generate(i % 256, i / 256, 0))
+        .collect();
+    assert!(values.iter().all(|v| (-50.0..=1500.0).contains(v)));
+
+    // Real terrain has more low ground than high ground
+    let mut sorted = values.clone();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted[sorted.len() / 2];
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    assert!(mean > median, "mean {} median {}", mean, median);
+    assert!(sorted[sorted.len() - 1] - sorted[0] > 300.0);
+
+    // Reproducible for a seed, different for another
+    let (same, other) = (make(5), make(6));
+    assert_eq!(terrain.generate(99, 42, 0), same.generate(99, 42, 0));
+    assert_ne!(terrain.generate(99, 42, 0), other.generate(99, 42, 0));
+}
+
+#[test]
+fn test_pixel_context() {
+    struct MapPattern;
+    impl DataGenerator for MapPattern {
+        fn generate(&self, _x: usize, _y: usize, _band: usize) -> f64 {
+            0.0
+        }
+
+        fn generate_at(&self, ctx: &PixelContext) -> f64 {
+            ctx.map_x * 1000.0 + ctx.map_y
+        }
+    }
+
+    let raster = RasterInfo {
+        width: 100,
+        height: 50,
+        bands: 2,
+        geotransform: GeoTransform {
+            x_min: 10.0,
+            pixel_width: 0.5,
+            rotation_x: 0.0,
+            y_max: 60.0,
+            rotation_y: 0.0,
+            pixel_height: -0.5,
+        },
+    };
+
+    // Map coordinates refer to the pixel centre
+    let ctx = PixelContext::new(4, 2, 1, &raster);
+    assert_eq!((ctx.map_x, ctx.map_y), (12.25, 58.75));
+    assert_eq!(ctx.raster.width, 100);
+
+    // Existing generators ignore the context, context-aware ones use it
+    assert_eq!(GradientPattern.generate_at(&ctx), 7.0);
+    assert_eq!(MapPattern.generate_at(&ctx), 12250.0 + 58.75);
+
+    // Wrappers pass the context through
+    let boxed: Box<dyn DataGenerator> = Box::new(MapPattern);
+    assert_eq!(boxed.generate_at(&ctx), 12250.0 + 58.75);
+    let holes = NoDataHoles::new(MapPattern, HoleMask::Rows { fraction: 0.0 }, -1.0);
+    assert_eq!(holes.generate_at(&ctx), 12250.0 + 58.75);
+}
+
+#[test]
+fn test_generate_block() {
+    let raster = RasterInfo {
+        width: 40,
+        height: 30,
+        bands: 2,
+        ..Default::default()
+    };
+    let window = Window {
+        x_off: 5,
+        y_off: 7,
+        width: 9,
+        height: 4,
+        band: 1,
+    };
+
+    let patterns: Vec<Box<dyn DataGenerator>> = vec![
+        Box::new(GradientPattern),
+        Box::new(UniformNoise::new(0.0, 1.0).seed(3)),
+        Box::new(NoDataHoles::new(
+            GradientPattern,
+            HoleMask::Scattered { fraction: 0.5 },
+            -1.0,
+        )),
+        Box::new(ConstantPattern(7.5)),
+        Box::new(MapRange::new(GradientPattern, -1.0, 1.0)),
+        Box::new(PerBand::new(Box::new(GradientPattern)).band(1, Box::new(SineWavePattern))),
+        Box::new(
+            PerlinNoise::new(0.1)
+                .scale(10.0)
+                .add(GradientPattern)
+                .mul(ConstantPattern(0.5))
+                .mix(NoisePattern.abs(), 0.25)
+                .offset(-3.0)
+                .clamp(0.0, 50.0)
+                .threshold(&[10.0, 20.0])
+                .select_band(0),
+        ),
+        Box::new(RingPattern::new(3)),
+        Box::new(GridPattern::new(4.0).line_width(2)),
+        Box::new(
+            CategoricalPattern::new([(1.0, 1.0), (2.0, 1.0)])
+                .unwrap()
+                .cell_size(4.0),
+        ),
+    ];
+
+    // Block generation matches pixel-by-pixel generation
+    for pattern in &patterns {
+        let mut buffer = vec![0.0; window.len()];
+        pattern.generate_block(&raster, &window, &mut buffer);
+        for (i, value) in buffer.iter().enumerate() {
+            let ctx = PixelContext::new(5 + i % 9, 7 + i / 9, 1, &raster);
+            assert_eq!(*value, pattern.generate_at(&ctx));
+        }
+    }
+}
+
+#[test]
+fn test_value_ranges() {
+    let raster = RasterInfo {
+        width: 100,
+        height: 50,
+        bands: 3,
+        ..Default::default()
+    };
+    assert_eq!(GradientPattern.value_range(&raster), Some((0.0, 150.0)));
+    assert_eq!(GradientPattern.generate(99, 49, 2), 150.0);
+
+    // Bounded patterns stay within their declared ranges
+    let patterns: Vec<Box<dyn DataGenerator>> = vec![
+        Box::new(SineWavePattern),
+        Box::new(NoisePattern),
+        Box::new(UniformNoise::new(5.0, -5.0)),
+        Box::new(PerlinNoise::new(0.1)),
+        Box::new(SimplexNoise::new(0.1)),
+        Box::new(Fbm::new(PerlinNoise::new(1.0))),
+        Box::new(TerrainPattern::new(100.0, 900.0)),
+        Box::new(RadialGradientPattern),
+    ];
+    for pattern in &patterns {
+        let (min, max) = pattern.value_range(&raster).unwrap();
+        for band in 0..3 {
+            for y in 0..50 {
+                for x in 0..100 {
+                    let value = pattern.generate_at(&PixelContext::new(x, y, band, &raster));
+                    assert!(
+                        (min..=max).contains(&value),
+                        "{} not in {:?}",
+                        value,
+                        (min, max)
+                    );
+                }
+            }
+        }
+    }
+
+    assert_eq!(
+        GaussianNoise::new(10.0, 2.0).value_range(&raster),
+        Some((2.0, 18.0))
+    );
+    assert_eq!(
+        PoissonNoise::new(4.0).value_range(&raster),
+        Some((0.0, 12.0))
+    );
+}
+
+#[test]
+fn test_map_range() {
+    let raster = RasterInfo::default();
+    let mapped = MapRange::new(NoisePattern, 0.0, 100.0);
+    assert_eq!(mapped.value_range(&raster), Some((0.0, 100.0)));
+    for y in 0..64 {
+        for x in 0..64 {
+            let value = mapped.generate(x, y, 0);
+            assert!((0.0..=100.0).contains(&value));
+            let expected = (NoisePattern.generate(x, y, 0) + 43758.5453) / 87517.0906 * 100.0;
+            assert!((value - expected).abs() < 1e-9);
+        }
+    }
+
+    // The tails of unbounded patterns are clamped
+    let mapped = MapRange::new(GaussianNoise::new(0.0, 1.0).seed(5), 0.0, 255.0);
+    for y in 0..256 {
+        for x in 0..256 {
+            assert!((0.0..=255.0).contains(&mapped.generate(x, y, 0)));
+        }
+    }
+
+    // An explicit source range overrides the declared one
+    let mapped = MapRange::new(GradientPattern, 0.0, 1.0).source_range(0.0, 10.0);
+    assert_eq!(mapped.generate(3, 2, 0), 0.5);
+    assert_eq!(mapped.generate(30, 20, 0), 1.0);
+
+    // The gradient's range depends on the raster size
+    let raster = RasterInfo {
+        width: 11,
+        height: 11,
+        bands: 1,
+        ..Default::default()
+    };
+    let mapped = MapRange::new(GradientPattern, 0.0, 1.0);
+    assert_eq!(
+        mapped.generate_at(&PixelContext::new(10, 10, 0, &raster)),
+        1.0
+    );
+    assert_eq!(
+        mapped.generate_at(&PixelContext::new(5, 5, 0, &raster)),
+        0.5
+    );
+}
+
+#[test]
+fn test_combinators() {
+    let raster = RasterInfo::default();
+    let (x, y, band) = (17, 9, 1);
+    let perlin = PerlinNoise::new(0.05).seed(2);
+    let p = perlin.generate(x, y, band);
+    let g = GradientPattern.generate(x, y, band);
+
+    assert_eq!(
+        PerlinNoise::new(0.05)
+            .seed(2)
+            .add(GradientPattern)
+            .generate(x, y, band),
+        p + g
+    );
+    assert_eq!(
+        PerlinNoise::new(0.05)
+            .seed(2)
+            .mul(GradientPattern)
+            .generate(x, y, band),
+        p * g
+    );
+    assert_eq!(
+        GradientPattern
+            .mix(ConstantPattern(100.0), 0.25)
+            .generate(x, y, band),
+        g * 0.75 + 25.0
+    );
+    assert_eq!(
+        GradientPattern.scale(2.0).offset(1.0).generate(x, y, band),
+        g * 2.0 + 1.0
+    );
+    assert_eq!(GradientPattern.clamp(0.0, 20.0).generate(x, y, band), 20.0);
+    // Swapped bounds are reordered, NaN bounds ignored
+    assert_eq!(GradientPattern.clamp(20.0, 0.0).generate(x, y, band), 20.0);
+    assert_eq!(
+        GradientPattern.clamp(f64::NAN, 5.0).generate(x, y, band),
+        5.0
+    );
+    assert_eq!(
+        GradientPattern.clamp(50.0, f64::NAN).generate(x, y, band),
+        50.0
+    );
+    assert_eq!(ConstantPattern(-4.0).abs().generate(x, y, band), 4.0);
+    assert_eq!(
+        GradientPattern.select_band(0).generate(x, y, band),
+        GradientPattern.generate(x, y, 0)
+    );
+
+    let classes = GradientPattern.threshold(&[10.0, 20.0]);
+    assert_eq!(classes.generate(3, 4, 0), 0.0);
+    assert_eq!(classes.generate(5, 5, 0), 1.0);
+    assert_eq!(classes.generate(30, 0, 0), 2.0);
+    assert_eq!(classes.value_range(&raster), Some((0.0, 2.0)));
+
+    // Value ranges follow the combinators
+    assert_eq!(
+        PerlinNoise::new(0.1)
+            .scale(-10.0)
+            .offset(5.0)
+            .value_range(&raster),
+        Some((-5.0, 15.0))
+    );
+    assert_eq!(
+        PerlinNoise::new(0.1)
+            .mul(UniformNoise::new(2.0, 3.0))
+            .value_range(&raster),
+        Some((-3.0, 3.0))
+    );
+    assert_eq!(
+        PerlinNoise::new(0.1)
+            .offset(-0.5)
+            .abs()
+            .value_range(&raster),
+        Some((0.0, 1.5))
+    );
+    assert_eq!(
+        SineWavePattern.clamp(100.0, 300.0).value_range(&raster),
+        Some((100.0, 255.0))
+    );
+    assert_eq!(
+        SineWavePattern.clamp(300.0, 100.0).value_range(&raster),
+        Some((100.0, 255.0))
+    );
+    assert_eq!(
+        SineWavePattern.clamp(f64::NAN, 10.0).value_range(&raster),
+        Some((0.0, 10.0))
+    );
+}
+
+#[test]
+fn test_per_band() {
+    let per_band = PerBand::new(Box::new(GradientPattern))
+        .band(1, Box::new(ConstantPattern(7.0)))
+        .band(3, Box::new(NoisePattern));
+
+    assert_eq!(per_band.generate(4, 5, 0), 9.0);
+    assert_eq!(per_band.generate(4, 5, 1), 7.0);
+    assert_eq!(per_band.generate(4, 5, 2), 11.0);
+    assert_eq!(per_band.generate(4, 5, 3), NoisePattern.generate(4, 5, 3));
+
+    // The declared range covers the bands in use
+    let raster = RasterInfo {
+        width: 10,
+        height: 10,
+        bands: 3,
+        ..Default::default()
+    };
+    assert_eq!(per_band.value_range(&raster), Some((0.0, 20.0)));
+}
+
+#[test]
+fn test_closure_pattern() {
+    let diagonal = |x: usize, y: usize, band: usize| (x * 2 + y + band) as f64;
+    assert_eq!(diagonal.generate(3, 4, 1), 11.0);
+
+    // Closures work with wrappers, combinators and block generation
+    let offset = 10.0;
+    let boxed: Box<dyn DataGenerator> =
+        Box::new(move |x: usize, _y: usize, _band: usize| x as f64 + offset);
+    let raster = RasterInfo::default();
+    let window = Window {
+        x_off: 2,
+        y_off: 0,
+        width: 3,
+        height: 1,
+        band: 0,
+    };
+    let mut buffer = vec![0.0; 3];
+    boxed
+        .scale(2.0)
+        .generate_block(&raster, &window, &mut buffer);
+    assert_eq!(buffer, vec![24.0, 26.0, 28.0]);
+}
+
+#[test]
+fn test_test_cards() {
+    let checkerboard = CheckerboardPattern::new(4);
+    assert_eq!(checkerboard.generate(0, 0, 0), 255.0);
+    assert_eq!(checkerboard.generate(3, 3, 1), 255.0);
+    assert_eq!(checkerboard.generate(4, 0, 0), 0.0);
+    assert_eq!(checkerboard.generate(4, 4, 0), 255.0);
+
+    let horizontal = StripePattern::horizontal(2);
+    assert_eq!(horizontal.generate(9, 1, 0), 255.0);
+    assert_eq!(horizontal.generate(0, 2, 0), 0.0);
+    let vertical = StripePattern::vertical(2);
+    assert_eq!(vertical.generate(1, 9, 0), 255.0);
+    assert_eq!(vertical.generate(2, 0, 0), 0.0);
+
+    // Centred patterns are centred on the raster passed in the context
+    let raster = RasterInfo {
+        width: 20,
+        height: 10,
+        ..Default::default()
+    };
+    let at = |pattern: &dyn DataGenerator, x, y| {
+        pattern.generate_at(&PixelContext::new(x, y, 0, &raster))
+    };
+
+    let rings = RingPattern::new(3);
+    assert_eq!(at(&rings, 10, 5), 255.0);
+    assert_eq!(at(&rings, 14, 5), 0.0);
+    assert_eq!(at(&rings, 17, 5), 255.0);
+
+    let radial = RadialGradientPattern;
+    assert!(at(&radial, 10, 5) < 20.0);
+    assert!(at(&radial, 0, 0) > 235.0);
+    assert!(at(&radial, 19, 9) > 235.0);
+
+    // Wedges alternate all around the centre
+    let star = SiemensStarPattern::new(2);
+    let quadrants = [
+        at(&star, 15, 7),
+        at(&star, 5, 7),
+        at(&star, 5, 2),
+        at(&star, 15, 2),
+    ];
+    assert_eq!(quadrants, [255.0, 0.0, 255.0, 0.0]);
+}
+
+#[test]
+fn test_grid_pattern() {
+    // 0.5 map units per pixel, so lines every 5 units are every 10 pixels
+    let raster = RasterInfo {
+        width: 40,
+        height: 40,
+        bands: 1,
+        geotransform: GeoTransform {
+            x_min: -3.0,
+            pixel_width: 0.5,
+            rotation_x: 0.0,
+            y_max: 12.0,
+            rotation_y: 0.0,
+            pixel_height: -0.5,
+        },
+    };
+    let grid = GridPattern::new(5.0);
+    let row: Vec<f64> = (0..40)
+        .map(|x| grid.generate_at(&PixelContext::new(x, 1, 0, &raster)))
+        .collect();
+    let lines: Vec<usize> = (0..40).filter(|&x| row[x] == 255.0).collect();
+    // x = 0, 5 and 10 are at pixels 6, 16 and 26; x = 15 is at 36
+    assert_eq!(lines, vec![6, 16, 26, 36]);
+
+    // Lines on a pixel boundary go to the pixel on their positive side, so
+    // y = 10, 5 and 0 are at rows 3, 13 and 23
+    let column: Vec<usize> = (0..40)
+        .filter(|&y| grid.generate_at(&PixelContext::new(1, y, 0, &raster)) == 255.0)
+        .collect();
+    assert_eq!(column, vec![3, 13, 23, 33]);
+
+    // Wider lines spread to the neighbouring pixels
+    let wide = GridPattern::new(5.0).line_width(3);
+    let lines: Vec<usize> = (0..20)
+        .filter(|&x| wide.generate_at(&PixelContext::new(x, 1, 0, &raster)) == 255.0)
+        .collect();
+    assert_eq!(lines, vec![5, 6, 7, 15, 16, 17]);
+}
+
+#[test]
+fn test_categorical_pattern() {
+    let classes = [(10.0, 0.5), (20.0, 0.3), (30.0, 0.2)];
+    let pattern = CategoricalPattern::new(classes)
+        .unwrap()
+        .cell_size(8.0)
+        .seed(3);
+    assert_eq!(
+        pattern.value_range(&RasterInfo::default()),
+        Some((10.0, 30.0))
+    );
+
+    let size = 512;
+    let values: Vec<f64> = (0..size * size)
+        .map(|i| pattern.generate(i % size, i / size, 0))
+        .collect();
+
+    // About 4000 cells, so the proportions are close to the targets
+    for (code, proportion) in classes {
+        let share = values.iter().filter(|&&v| v == code).count() as f64 / values.len() as f64;
+        assert!(
+            (share - proportion).abs() < 0.05,
+            "class {} covers {} instead of {}",
+            code,
+            share,
+            proportion
+        );
+    }
+
+    // Classes form contiguous regions: they change at cell edges, not at
+    // most pixels as per-pixel noise would
+    let changes = (0..values.len() - 1)
+        .filter(|&i| (i + 1) % size != 0 && values[i] != values[i + 1])
+        .count();
+    assert!(changes < values.len() / 5, "{} class changes", changes);
+
+    // Bands share the classes, and seeds change them
+    assert_eq!(pattern.generate(100, 50, 2), values[50 * size + 100]);
+    let reseeded = CategoricalPattern::new(classes)
+        .unwrap()
+        .cell_size(8.0)
+        .seed(4);
+    assert!((0..size).any(|x| reseeded.generate(x, 0, 0) != values[x]));
+}
+
+#[test]
+fn test_categorical_pattern_invalid_classes() {
+    let invalid: [&[(f64, f64)]; 5] = [
+        &[],
+        &[(1.0, 0.0), (2.0, 0.0)],
+        &[(1.0, -1.0), (2.0, 2.0)],
+        &[(1.0, f64::NAN), (2.0, 1.0)],
+        &[(1.0, f64::INFINITY), (2.0, 1.0)],
+    ];
+    for classes in invalid {
+        match CategoricalPattern::new(classes.iter().copied()) {
+            Err(GeoTiffError::InvalidParameter(_)) => {}
+            _ => panic!("Expected InvalidParameter error for {:?}", classes),
+        }
+    }
+}
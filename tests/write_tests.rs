@@ -0,0 +1,400 @@
+use gdal::raster::RgbaEntry;
+use gdal::{Dataset, Metadata};
+use rasterfakers::colors::{Color, ColorTable};
+use rasterfakers::conversions::Complex;
+use rasterfakers::rat::{FieldType, FieldUsage, RasterAttributeTable, RatValue};
+use rasterfakers::{
+    validate_cog, BigTiff, ConstantPattern, FakeGeoTiffBuilder, GeoTiffError, HoleMask, Interleave,
+    Layout, OverviewStorage, Overviews, PerlinNoise,
+};
+use std::path::PathBuf;
+
+/// A fresh path for a test's output file, in a directory of the system's
+/// temporary directory.
+fn output_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("rasterfakers_tests");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// A value unique to every pixel and band of a raster up to 1000 pixels wide.
+fn pixel_id(x: usize, y: usize, band: usize) -> f64 {
+    (band * 1_000_000 + y * 1000 + x) as f64
+}
+
+/// Reads a band back and checks each pixel against [`pixel_id`].
+fn assert_pixel_ids(dataset: &Dataset, band: usize) {
+    let (width, height) = dataset.raster_size();
+    let buffer = dataset
+        .rasterband(band + 1)
+        .unwrap()
+        .read_band_as::<i32>()
+        .unwrap();
+    assert_eq!(buffer.shape(), (width, height));
+    for (i, &value) in buffer.data().iter().enumerate() {
+        let (x, y) = (i % width, i / width);
+        assert_eq!(
+            value as f64,
+            pixel_id(x, y, band),
+            "pixel ({}, {}) of band {}",
+            x,
+            y,
+            band
+        );
+    }
+}
+
+#[test]
+fn test_write_tiles_with_partial_edge_blocks() {
+    // 100x70 with 32x32 tiles leaves partial tiles on the right and bottom
+    let path = output_path("tiles_edges.tiff");
+    FakeGeoTiffBuilder::new()
+        .dimensions(100, 70)
+        .unwrap()
+        .bands(2)
+        .unwrap()
+        .data_fn(pixel_id)
+        .layout(Layout::Tiles {
+            width: 32,
+            height: 32,
+        })
+        .output_path(&path)
+        .build::<i32>()
+        .unwrap()
+        .write()
+        .unwrap();
+
+    let dataset = Dataset::open(&path).unwrap();
+    assert_eq!(dataset.raster_size(), (100, 70));
+    assert_eq!(dataset.rasterband(1).unwrap().block_size(), (32, 32));
+    assert_pixel_ids(&dataset, 0);
+    assert_pixel_ids(&dataset, 1);
+}
+
+#[test]
+fn test_write_strips_with_remainder() {
+    // 70 rows in strips of 16 leaves a last strip of 6 rows
+    let path = output_path("strips_remainder.tiff");
+    FakeGeoTiffBuilder::new()
+        .dimensions(100, 70)
+        .unwrap()
+        .data_fn(pixel_id)
+        .layout(Layout::Strips { rows_per_strip: 16 })
+        .output_path(&path)
+        .build::<i32>()
+        .unwrap()
+        .write()
+        .unwrap();
+
+    let dataset = Dataset::open(&path).unwrap();
+    assert_eq!(dataset.rasterband(1).unwrap().block_size(), (100, 16));
+    assert_pixel_ids(&dataset, 0);
+}
+
+#[test]
+fn test_write_bigtiff() {
+    let read_version = |path: &PathBuf| {
+        let header = std::fs::read(path).unwrap();
+        // Byte order mark, then the version: 42 for classic TIFF, 43 for BigTIFF
+        match &header[..2] {
+            b"II" => u16::from_le_bytes([header[2], header[3]]),
+            _ => u16::from_be_bytes([header[2], header[3]]),
+        }
+    };
+
+    for (bigtiff, version) in [(BigTiff::Yes, 43), (BigTiff::No, 42)] {
+        let path = output_path(&format!("bigtiff_{}.tiff", version));
+        FakeGeoTiffBuilder::new()
+            .dimensions(50, 40)
+            .unwrap()
+            .data_fn(pixel_id)
+            .layout(Layout::Tiles {
+                width: 16,
+                height: 16,
+            })
+            .bigtiff(bigtiff)
+            .output_path(&path)
+            .build::<i32>()
+            .unwrap()
+            .write()
+            .unwrap();
+
+        assert_eq!(read_version(&path), version, "{:?}", bigtiff);
+        assert_pixel_ids(&Dataset::open(&path).unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_write_complex_nodata_holes() {
+    let hole = HoleMask::Rect {
+        x: 0,
+        y: 0,
+        width: 4,
+        height: 2,
+    };
+    for imaginary in [Some(3.0), None] {
+        let path = output_path("complex_holes.tiff");
+        let mut builder = FakeGeoTiffBuilder::new()
+            .dimensions(8, 4)
+            .unwrap()
+            .data_generator(Box::new(ConstantPattern(5.0)))
+            .nodata(-9999.0)
+            .nodata_holes(hole.clone(), 0)
+            .output_path(&path);
+        if let Some(imaginary) = imaginary {
+            builder = builder.imaginary_generator(Box::new(ConstantPattern(imaginary)));
+        }
+        builder.build::<Complex<f32>>().unwrap().write().unwrap();
+
+        let buffer = Dataset::open(&path)
+            .unwrap()
+            .rasterband(1)
+            .unwrap()
+            .read_band_as::<Complex<f32>>()
+            .unwrap();
+        for (i, &value) in buffer.data().iter().enumerate() {
+            let expected = if i % 8 < 4 && i / 8 < 2 {
+                Complex {
+                    re: -9999.0,
+                    im: -9999.0,
+                }
+            } else {
+                Complex {
+                    re: 5.0,
+                    im: imaginary.unwrap_or(0.0) as f32,
+                }
+            };
+            assert_eq!(value, expected, "pixel {} with {:?}", i, imaginary);
+        }
+    }
+}
+
+#[test]
+fn test_write_identical_with_threads() {
+    let write = |threads: usize| {
+        let path = output_path(&format!("threads_{}.tiff", threads));
+        FakeGeoTiffBuilder::new()
+            .dimensions(300, 200)
+            .unwrap()
+            .bands(3)
+            .unwrap()
+            .data_generator(Box::new(PerlinNoise::new(0.02).seed(4)))
+            .layout(Layout::Tiles {
+                width: 64,
+                height: 64,
+            })
+            .threads(threads)
+            .output_path(&path)
+            .build::<f32>()
+            .unwrap()
+            .write()
+            .unwrap();
+        std::fs::read(path).unwrap()
+    };
+
+    let single = write(1);
+    // usize::MAX is capped to the available cores
+    for threads in [8, 0, usize::MAX] {
+        assert!(write(threads) == single, "{} threads", threads);
+    }
+}
+
+#[test]
+fn test_cog_layout_validation() {
+    let builder = || {
+        FakeGeoTiffBuilder::new()
+            .dimensions(1024, 1024)
+            .unwrap()
+            .data_generator(Box::new(PerlinNoise::new(0.01)))
+            .layout(Layout::Tiles {
+                width: 256,
+                height: 256,
+            })
+            .overviews(Overviews::Factors(vec![2, 4]))
+    };
+
+    let cog = output_path("valid_cog.tiff");
+    builder()
+        .cloud_optimized(true)
+        .output_path(&cog)
+        .build::<u8>()
+        .unwrap()
+        .write()
+        .unwrap();
+    validate_cog(&cog).unwrap();
+
+    // A plain GeoTIFF with overviews added after its data, with the IFDs
+    // scattered through the file
+    let plain = output_path("plain_with_overviews.tiff");
+    builder()
+        .overview_storage(OverviewStorage::Internal)
+        .output_path(&plain)
+        .build::<u8>()
+        .unwrap()
+        .write()
+        .unwrap();
+    assert!(matches!(
+        validate_cog(&plain),
+        Err(GeoTiffError::InvalidCog(_))
+    ));
+}
+
+#[test]
+fn test_write_interleave() {
+    let write = |interleave, cloud_optimized| {
+        let path = output_path(&format!("{:?}_{}.tiff", interleave, cloud_optimized));
+        FakeGeoTiffBuilder::new()
+            .dimensions(100, 70)
+            .unwrap()
+            .bands(3)
+            .unwrap()
+            .data_fn(pixel_id)
+            .interleave(interleave)
+            .cloud_optimized(cloud_optimized)
+            .output_path(&path)
+            .build::<i32>()
+            .unwrap()
+            .write()
+            .unwrap();
+        Dataset::open(&path).unwrap()
+    };
+
+    for (interleave, cloud_optimized, expected) in [
+        (Interleave::Band, false, "BAND"),
+        (Interleave::Pixel, false, "PIXEL"),
+        (Interleave::Pixel, true, "PIXEL"),
+    ] {
+        let dataset = write(interleave, cloud_optimized);
+        assert_eq!(
+            dataset.metadata_item("INTERLEAVE", "IMAGE_STRUCTURE"),
+            Some(expected.to_string())
+        );
+        for band in 0..3 {
+            assert_pixel_ids(&dataset, band);
+        }
+    }
+}
+
+/// Reads the pixel count column of a band's attribute table.
+fn read_pixel_counts(dataset: &Dataset, band: usize) -> Vec<f64> {
+    let band = dataset.rasterband(band + 1).unwrap();
+    unsafe {
+        let rat = gdal_sys::GDALGetDefaultRAT(band.c_rasterband());
+        assert!(!rat.is_null(), "no attribute table");
+        let column =
+            gdal_sys::GDALRATGetColOfUsage(rat, gdal_sys::GDALRATFieldUsage::GFU_PixelCount);
+        assert!(column >= 0, "no pixel count column");
+        (0..gdal_sys::GDALRATGetRowCount(rat))
+            .map(|row| gdal_sys::GDALRATGetValueAsDouble(rat, row, column))
+            .collect()
+    }
+}
+
+#[test]
+fn test_write_attribute_table_pixel_counts() {
+    // Every value from 0 to 99 in 10 rows, with 5 as nodata
+    let path = output_path("pixel_counts.tiff");
+    let ranges = RasterAttributeTable::new()
+        .column("Min", FieldType::Integer, FieldUsage::Min)
+        .column("Max", FieldType::Integer, FieldUsage::Max)
+        .row([RatValue::from(0), 99.into()])
+        .row([RatValue::from(10), 19.into()])
+        .row([RatValue::from(20), 29.into()])
+        .pixel_counts("Count");
+    let values = RasterAttributeTable::new()
+        .column("Value", FieldType::Integer, FieldUsage::MinMax)
+        .row([RatValue::from(4)])
+        .row([RatValue::from(5)])
+        .row([RatValue::from(150)])
+        .pixel_counts("Count");
+    FakeGeoTiffBuilder::new()
+        .dimensions(100, 10)
+        .unwrap()
+        .bands(2)
+        .unwrap()
+        .data_fn(|x, _, _| x as f64)
+        .nodata(5.0)
+        .attribute_table(0, ranges)
+        .attribute_table(1, values)
+        .output_path(&path)
+        .build::<u8>()
+        .unwrap()
+        .write()
+        .unwrap();
+
+    let dataset = Dataset::open(&path).unwrap();
+    // Nested ranges take their values from the outer one, and nodata is
+    // counted nowhere
+    assert_eq!(read_pixel_counts(&dataset, 0), [790.0, 100.0, 100.0]);
+    assert_eq!(read_pixel_counts(&dataset, 1), [10.0, 0.0, 0.0]);
+}
+
+/// Reads the category names of a band, indexed by pixel value.
+fn read_category_names(dataset: &Dataset, band: usize) -> Vec<String> {
+    let band = dataset.rasterband(band + 1).unwrap();
+    let mut names = Vec::new();
+    unsafe {
+        let list = gdal_sys::GDALGetRasterCategoryNames(band.c_rasterband());
+        assert!(!list.is_null(), "no category names");
+        while !(*list.add(names.len())).is_null() {
+            let name = std::ffi::CStr::from_ptr(*list.add(names.len()));
+            names.push(name.to_string_lossy().into_owned());
+        }
+    }
+    names
+}
+
+#[test]
+fn test_write_cog_band_metadata() {
+    // Alternating columns of 0 and 1
+    let path = output_path("cog_metadata.tiff");
+    let classes = RasterAttributeTable::new()
+        .column("Value", FieldType::Integer, FieldUsage::MinMax)
+        .column("Class", FieldType::String, FieldUsage::Name)
+        .row([RatValue::from(0), "Water".into()])
+        .row([RatValue::from(1), "Forest".into()])
+        .pixel_counts("Count");
+    FakeGeoTiffBuilder::new()
+        .dimensions(64, 64)
+        .unwrap()
+        .data_fn(|x, _, _| (x % 2) as f64)
+        .color_table(
+            ColorTable::new()
+                .color(0, Color::rgb(0, 0, 255))
+                .color(1, Color::rgb(0, 128, 0)),
+        )
+        .category_names(0, [(0, "Water"), (1, "Forest")])
+        .attribute_table(0, classes)
+        .cloud_optimized(true)
+        .output_path(&path)
+        .build::<u8>()
+        .unwrap()
+        .write()
+        .unwrap();
+
+    let dataset = Dataset::open(&path).unwrap();
+    let band = dataset.rasterband(1).unwrap();
+    let color_table = band.color_table().unwrap();
+    assert_eq!(
+        color_table.entry_as_rgb(0),
+        Some(RgbaEntry {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 255
+        })
+    );
+    assert_eq!(
+        color_table.entry_as_rgb(1),
+        Some(RgbaEntry {
+            r: 0,
+            g: 128,
+            b: 0,
+            a: 255
+        })
+    );
+    assert_eq!(read_category_names(&dataset, 0), ["Water", "Forest"]);
+    assert_eq!(read_pixel_counts(&dataset, 0), [2048.0, 2048.0]);
+}